@@ -1,31 +1,106 @@
 use std::num::NonZero;
 
+use wgpu::{BindGroupLayoutEntry, BindingType, BufferBindingType, BufferUsages, TextureUsages};
+
 use crate::{
-    PassContext, Ref, ResourceRead, TransientBuffer, TransientTexture,
+    BindGroupKey, BindingKey, FrameGraphError, PassContext, Ref, ResourceRead, ResourceUsages,
+    ResourceView, ResourceWrite, TransientBuffer, TransientTexture,
     gfx_base::{
         BindGroupDescriptor, BindGroupLayout, BufferBinding, GpuBindGroup, GpuBindGroupEntry,
-        GpuBindingResource, Sampler, TextureViewDescriptor,
+        GpuBindingResource, GpuBuffer, GpuTexture, GpuTextureView, Sampler, TextureViewDescriptor,
     },
 };
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct TransientBindGroupBuffer {
-    pub buffer: Ref<TransientBuffer, ResourceRead>,
+pub type TransientBindGroupStorageBuffer = TransientBindGroupBuffer<ResourceWrite>;
+
+pub type TransientBindGroupStorageTextureView = TransientBindGroupTextureView<ResourceWrite>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransientBindGroupBuffer<ViewType = ResourceRead> {
+    pub buffer: Ref<TransientBuffer, ViewType>,
     pub size: Option<NonZero<u64>>,
     pub offset: u64,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct TransientBindGroupTextureView {
-    pub texture: Ref<TransientTexture, ResourceRead>,
+impl<ViewType: ResourceView> TransientBindGroupBuffer<ViewType> {
+    pub fn get_buffer_binding(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<BufferBinding, FrameGraphError> {
+        let buffer = context.try_get_resource(&self.buffer)?;
+
+        Ok(BufferBinding {
+            buffer: buffer.resource.clone(),
+            offset: self.offset,
+            size: self.size,
+        })
+    }
+
+    fn get_binding_key(&self, context: &PassContext<'_>) -> Result<BindingKey, FrameGraphError> {
+        Ok(BindingKey::Buffer {
+            buffer: context.try_get_resource(&self.buffer)?.resource.clone(),
+            offset: self.offset,
+            size: self.size,
+        })
+    }
+
+    fn get_buffer_and_range(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<(GpuBuffer, u64, Option<NonZero<u64>>), FrameGraphError> {
+        let buffer = context.try_get_resource(&self.buffer)?;
+        Ok((buffer.resource.clone(), self.offset, self.size))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransientBindGroupTextureView<ViewType = ResourceRead> {
+    pub texture: Ref<TransientTexture, ViewType>,
     pub texture_view_desc: TextureViewDescriptor,
 }
 
+impl<ViewType: ResourceView> TransientBindGroupTextureView<ViewType> {
+    /// `texture_view_desc`, with the mips and layers `texture` is scoped to taking
+    /// precedence over its own.
+    pub fn get_view_desc(&self) -> TextureViewDescriptor {
+        let mut desc = self.texture_view_desc.clone();
+        self.texture.raw.subresource.apply(&mut desc);
+        desc
+    }
+
+    pub fn get_texture_view(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<GpuTextureView, FrameGraphError> {
+        let texture = context.try_get_resource(&self.texture)?;
+
+        Ok(context
+            .texture_view_cache()
+            .get_or_create(&texture.resource, &self.get_view_desc()))
+    }
+
+    fn get_texture_and_view_desc(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<(GpuTexture, TextureViewDescriptor), FrameGraphError> {
+        let texture = context.try_get_resource(&self.texture)?;
+        Ok((texture.resource.clone(), self.get_view_desc()))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TransientBindGroupResource {
     Buffer(TransientBindGroupBuffer),
+    /// A storage buffer the shader writes. The graph sees the write through the
+    /// `ResourceWrite` reference and adds `STORAGE` to the buffer's usage.
+    StorageBuffer(TransientBindGroupStorageBuffer),
+    BufferArray(Vec<TransientBindGroupBuffer>),
     Sampler(Sampler),
+    SamplerArray(Vec<Sampler>),
     TextureView(TransientBindGroupTextureView),
+    /// A storage texture the shader writes, bound write-only or read-write as the
+    /// layout says. Adds `STORAGE_BINDING` to the texture's usage.
+    StorageTextureView(TransientBindGroupStorageTextureView),
     TextureViewArray(Vec<TransientBindGroupTextureView>),
 }
 
@@ -36,47 +111,146 @@ pub struct TransientBindGroupEntry {
 }
 
 impl TransientBindGroupEntry {
-    pub fn get_gpu_bind_group_entry(&self, context: &PassContext<'_>) -> GpuBindGroupEntry {
-        match &self.resource {
+    /// Resolves the binding to the physical resources it refers to this frame.
+    pub(crate) fn get_binding_key(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<BindingKey, FrameGraphError> {
+        let key = match &self.resource {
+            TransientBindGroupResource::Buffer(binding) => binding.get_binding_key(context)?,
+            TransientBindGroupResource::StorageBuffer(binding) => {
+                binding.get_binding_key(context)?
+            }
+            TransientBindGroupResource::BufferArray(bindings) => BindingKey::BufferArray(
+                bindings
+                    .iter()
+                    .map(|binding| binding.get_buffer_and_range(context))
+                    .collect::<Result<_, FrameGraphError>>()?,
+            ),
+            TransientBindGroupResource::Sampler(sampler) => BindingKey::Sampler(sampler.id()),
+            TransientBindGroupResource::SamplerArray(samplers) => {
+                BindingKey::SamplerArray(samplers.iter().map(Sampler::id).collect())
+            }
+            TransientBindGroupResource::TextureView(binding) => {
+                let (texture, desc) = binding.get_texture_and_view_desc(context)?;
+                BindingKey::TextureView { texture, desc }
+            }
+            TransientBindGroupResource::StorageTextureView(binding) => {
+                let (texture, desc) = binding.get_texture_and_view_desc(context)?;
+                BindingKey::TextureView { texture, desc }
+            }
+            TransientBindGroupResource::TextureViewArray(bindings) => BindingKey::TextureViewArray(
+                bindings
+                    .iter()
+                    .map(|binding| binding.get_texture_and_view_desc(context))
+                    .collect::<Result<_, FrameGraphError>>()?,
+            ),
+        };
+
+        Ok(key)
+    }
+
+    pub fn get_gpu_bind_group_entry(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<GpuBindGroupEntry, FrameGraphError> {
+        let resource = match &self.resource {
             TransientBindGroupResource::Buffer(binding) => {
-                let buffer = context.resource_table().get_resource(&binding.buffer);
-
-                GpuBindGroupEntry {
-                    binding: self.binding,
-                    resource: GpuBindingResource::Buffer(BufferBinding {
-                        buffer: buffer.resource.clone(),
-                        offset: binding.offset,
-                        size: binding.size,
-                    }),
-                }
+                GpuBindingResource::Buffer(binding.get_buffer_binding(context)?)
+            }
+            TransientBindGroupResource::StorageBuffer(binding) => {
+                GpuBindingResource::Buffer(binding.get_buffer_binding(context)?)
+            }
+            TransientBindGroupResource::BufferArray(bindings) => GpuBindingResource::BufferArray(
+                bindings
+                    .iter()
+                    .map(|binding| binding.get_buffer_binding(context))
+                    .collect::<Result<_, FrameGraphError>>()?,
+            ),
+            TransientBindGroupResource::Sampler(sampler) => {
+                GpuBindingResource::Sampler(sampler.value().clone())
             }
-            TransientBindGroupResource::Sampler(sampler) => GpuBindGroupEntry {
-                binding: self.binding,
-                resource: GpuBindingResource::Sampler(sampler.value().clone()),
-            },
+            TransientBindGroupResource::SamplerArray(samplers) => GpuBindingResource::SamplerArray(
+                samplers
+                    .iter()
+                    .map(|sampler| sampler.value().clone())
+                    .collect(),
+            ),
             TransientBindGroupResource::TextureView(binding) => {
-                let texture = context.resource_table().get_resource(&binding.texture);
-                let texture_view = texture.resource.create_view(&binding.texture_view_desc);
+                GpuBindingResource::TextureView(binding.get_texture_view(context)?)
+            }
+            TransientBindGroupResource::StorageTextureView(binding) => {
+                GpuBindingResource::TextureView(binding.get_texture_view(context)?)
+            }
+            TransientBindGroupResource::TextureViewArray(bindings) => {
+                GpuBindingResource::TextureViewArray(
+                    bindings
+                        .iter()
+                        .map(|binding| binding.get_texture_view(context))
+                        .collect::<Result<_, FrameGraphError>>()?,
+                )
+            }
+        };
+
+        Ok(GpuBindGroupEntry {
+            binding: self.binding,
+            resource,
+        })
+    }
+
+    /// Read-only bindings take their usage from `layout_entry`: buffers are bound
+    /// as uniform or storage, textures as sampled or storage. Without an entry,
+    /// buffers add no usage and textures are assumed sampled. Written bindings can
+    /// only be storage.
+    pub fn collect_usages(
+        &self,
+        layout_entry: Option<&BindGroupLayoutEntry>,
+        usages: &mut ResourceUsages,
+    ) {
+        let binding_type = layout_entry.map(|entry| &entry.ty);
+
+        let buffer_usage = match binding_type {
+            Some(BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                ..
+            }) => BufferUsages::UNIFORM,
+            Some(BindingType::Buffer {
+                ty: BufferBindingType::Storage { .. },
+                ..
+            }) => BufferUsages::STORAGE,
+            _ => BufferUsages::empty(),
+        };
 
-                GpuBindGroupEntry {
-                    binding: self.binding,
-                    resource: GpuBindingResource::TextureView(texture_view),
+        let texture_usage = match binding_type {
+            Some(BindingType::StorageTexture { .. }) => TextureUsages::STORAGE_BINDING,
+            _ => TextureUsages::TEXTURE_BINDING,
+        };
+
+        match &self.resource {
+            TransientBindGroupResource::Buffer(binding) => {
+                usages.add_buffer(&binding.buffer, buffer_usage);
+            }
+            TransientBindGroupResource::StorageBuffer(binding) => {
+                usages.add_buffer(&binding.buffer, BufferUsages::STORAGE);
+            }
+            TransientBindGroupResource::BufferArray(bindings) => {
+                for binding in bindings.iter() {
+                    usages.add_buffer(&binding.buffer, buffer_usage);
                 }
             }
+            TransientBindGroupResource::TextureView(binding) => {
+                usages.add_texture(&binding.texture, texture_usage);
+            }
+            TransientBindGroupResource::StorageTextureView(binding) => {
+                usages.add_texture(&binding.texture, TextureUsages::STORAGE_BINDING);
+            }
             TransientBindGroupResource::TextureViewArray(bindings) => {
-                let bindings = bindings
-                    .iter()
-                    .map(|binding| {
-                        let texture = context.resource_table().get_resource(&binding.texture);
-                        texture.resource.create_view(&binding.texture_view_desc)
-                    })
-                    .collect();
-
-                GpuBindGroupEntry {
-                    binding: self.binding,
-                    resource: GpuBindingResource::TextureViewArray(bindings),
+                for binding in bindings.iter() {
+                    usages.add_texture(&binding.texture, texture_usage);
                 }
             }
+            TransientBindGroupResource::Sampler(_)
+            | TransientBindGroupResource::SamplerArray(_) => {}
         }
     }
 }
@@ -89,19 +263,42 @@ pub struct TransientBindGroup {
 }
 
 impl TransientBindGroup {
-    pub fn create_bind_group(&self, context: &PassContext<'_>) -> GpuBindGroup {
-        let entries = self
-            .entries
-            .iter()
-            .map(|entry| entry.get_gpu_bind_group_entry(context))
-            .collect();
-
-        let desc = BindGroupDescriptor {
-            label: self.label.clone(),
-            layout: self.layout.value().clone(),
-            entries,
+    /// Returns the bind group from the context's [`BindGroupCache`](crate::BindGroupCache)
+    /// if one was created from the same layout and physical resources, and creates
+    /// and caches it otherwise.
+    pub fn create_bind_group(
+        &self,
+        context: &PassContext<'_>,
+    ) -> Result<GpuBindGroup, FrameGraphError> {
+        let key = BindGroupKey {
+            layout: self.layout.id(),
+            entries: self
+                .entries
+                .iter()
+                .map(|entry| Ok((entry.binding, entry.get_binding_key(context)?)))
+                .collect::<Result<_, FrameGraphError>>()?,
         };
 
-        context.render_device().create_bind_group(&desc)
+        context.bind_group_cache().try_get_or_create(key, || {
+            let entries = self
+                .entries
+                .iter()
+                .map(|entry| entry.get_gpu_bind_group_entry(context))
+                .collect::<Result<_, _>>()?;
+
+            let desc = BindGroupDescriptor {
+                label: self.label.clone(),
+                layout: self.layout.value().clone(),
+                entries,
+            };
+
+            Ok(context.render_device().create_bind_group(&desc))
+        })
+    }
+
+    pub fn collect_usages(&self, usages: &mut ResourceUsages) {
+        for entry in self.entries.iter() {
+            entry.collect_usages(self.layout.get_entry(entry.binding), usages);
+        }
     }
 }
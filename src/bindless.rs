@@ -0,0 +1,90 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{
+    TransientBindGroupBuffer, TransientBindGroupEntry, TransientBindGroupResource,
+    TransientBindGroupTextureView,
+};
+
+/// A binding that can be gathered into a binding array.
+pub trait BindlessBinding: Clone + Eq + Hash {
+    fn into_array_resource(bindings: Vec<Self>) -> TransientBindGroupResource;
+}
+
+impl BindlessBinding for TransientBindGroupTextureView {
+    fn into_array_resource(bindings: Vec<Self>) -> TransientBindGroupResource {
+        TransientBindGroupResource::TextureViewArray(bindings)
+    }
+}
+
+impl BindlessBinding for TransientBindGroupBuffer {
+    fn into_array_resource(bindings: Vec<Self>) -> TransientBindGroupResource {
+        TransientBindGroupResource::BufferArray(bindings)
+    }
+}
+
+pub type BindlessTextureTable = BindlessTable<TransientBindGroupTextureView>;
+
+pub type BindlessBufferTable = BindlessTable<TransientBindGroupBuffer>;
+
+/// Gathers many texture views or buffers into one binding array, handing out the
+/// index each is bound at so shaders can look them up. Inserting the same binding
+/// twice returns the same index.
+///
+/// `capacity` is the `count` of the array in the bind group layout. Tables holding
+/// fewer bindings than that rely on `Features::PARTIALLY_BOUND_BINDING_ARRAY`.
+pub struct BindlessTable<Binding: BindlessBinding> {
+    bindings: Vec<Binding>,
+    indices: HashMap<Binding, u32>,
+    capacity: u32,
+}
+
+impl<Binding: BindlessBinding> BindlessTable<Binding> {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            bindings: vec![],
+            indices: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Index of `binding` in the array, or `None` if it is new and the table is full.
+    pub fn insert(&mut self, binding: Binding) -> Option<u32> {
+        if let Some(index) = self.indices.get(&binding) {
+            return Some(*index);
+        }
+
+        if self.len() >= self.capacity {
+            return None;
+        }
+
+        let index = self.len();
+        self.indices.insert(binding.clone(), index);
+        self.bindings.push(binding);
+
+        Some(index)
+    }
+
+    pub fn get(&self, index: u32) -> Option<&Binding> {
+        self.bindings.get(index as usize)
+    }
+
+    pub fn len(&self) -> u32 {
+        self.bindings.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Bind group entry binding the whole table at `binding`.
+    pub fn entry(&self, binding: u32) -> TransientBindGroupEntry {
+        TransientBindGroupEntry {
+            binding,
+            resource: Binding::into_array_resource(self.bindings.clone()),
+        }
+    }
+}
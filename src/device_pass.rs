@@ -1,4 +1,9 @@
-use crate::FrameGraphContext;
+use wgpu::CommandBuffer;
+
+use crate::{
+    BindGroupCache, FrameGraphContext, FrameGraphError, ResourceTable, TextureViewCache,
+    gfx_base::{PipelineContainer, RenderDevice, RenderDeviceEvent},
+};
 
 use super::{FrameGraph, IndexHandle, Pass, PassNode, ResourceRelease, ResourceRequese};
 
@@ -23,24 +28,75 @@ impl DevicePass {
 
     pub fn release_resources(&self, context: &mut FrameGraphContext) {
         for handle in self.resource_release_array.iter() {
-            context
-                .resource_table
-                .release_resource(handle, context.transient_resource_cache);
+            context.resource_table.release_resource(
+                handle,
+                &context.render_device,
+                context.transient_resource_cache,
+            );
         }
     }
 
-    pub fn execute(&self, context: &mut FrameGraphContext) {
-        self.request_resources(context);
-
-        if let Some(pass) = &self.pass {
-            pass.render(
-                &mut context.command_buffers,
+    pub fn release_resources_retained(&self, context: &mut FrameGraphContext) {
+        for handle in self.resource_release_array.iter() {
+            context.resource_table.release_resource_retained(
+                handle,
                 &context.render_device,
-                &context.resource_table,
-                &context.pipeline_container,
+                context.transient_resource_cache,
             );
         }
+    }
+
+    /// Records the pass's commands into `command_buffers`, without touching resource
+    /// ownership, so passes can be recorded on any thread once their resources are
+    /// in `resource_table`.
+    pub fn record(
+        &self,
+        command_buffers: &mut Vec<CommandBuffer>,
+        render_device: &RenderDevice,
+        resource_table: &ResourceTable,
+        pipeline_container: &PipelineContainer,
+        bind_group_cache: &BindGroupCache,
+        texture_view_cache: &TextureViewCache,
+    ) -> Result<(), FrameGraphError> {
+        let result = match &self.pass {
+            Some(pass) => pass.render(
+                command_buffers,
+                render_device,
+                resource_table,
+                pipeline_container,
+                bind_group_cache,
+                texture_view_cache,
+            ),
+            None => Ok(()),
+        };
+
+        result.map_err(|error| FrameGraphError::Pass {
+            pass: self.name.clone(),
+            source: Box::new(error),
+        })
+    }
+
+    /// Records the pass. On failure the pass's commands are dropped, but its
+    /// resources are still released so later passes are unaffected.
+    pub fn execute(&self, context: &mut FrameGraphContext) -> Result<(), FrameGraphError> {
+        context
+            .render_device
+            .record(RenderDeviceEvent::ExecutePass {
+                name: self.name.clone(),
+            });
+        self.request_resources(context);
+
+        let result = self.record(
+            &mut context.command_buffers,
+            &context.render_device,
+            &context.resource_table,
+            &context.pipeline_container,
+            context.transient_resource_cache.bind_group_cache(),
+            context.transient_resource_cache.texture_view_cache(),
+        );
         self.release_resources(context);
+
+        result
     }
 
     pub fn extra(&mut self, graph: &mut FrameGraph, index: IndexHandle<PassNode>) {
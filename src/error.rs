@@ -0,0 +1,75 @@
+use core::fmt::{Display, Formatter, Result};
+
+use crate::{CompileError, IndexHandle, ResourceNode, gfx_base::CachedPipelineId};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameGraphError {
+    Compile(CompileError),
+    /// The resource was never requested, or was already released.
+    MissingResource {
+        index: IndexHandle<ResourceNode>,
+    },
+    /// The resource exists but is not of the type the handle expects.
+    ResourceTypeMismatch {
+        index: IndexHandle<ResourceNode>,
+        expected: &'static str,
+    },
+    MissingRenderPipeline {
+        id: CachedPipelineId,
+    },
+    MissingComputePipeline {
+        id: CachedPipelineId,
+    },
+    /// A pass failed and was skipped.
+    Pass {
+        pass: String,
+        source: Box<FrameGraphError>,
+    },
+    /// One or more passes failed while executing the graph.
+    Execute {
+        errors: Vec<FrameGraphError>,
+    },
+    /// The graph has passes but was executed without being compiled.
+    NotCompiled,
+}
+
+impl Display for FrameGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            FrameGraphError::Compile(error) => write!(f, "{error}"),
+            FrameGraphError::MissingResource { index } => {
+                write!(f, "resource {} is not available", index.index)
+            }
+            FrameGraphError::ResourceTypeMismatch { index, expected } => {
+                write!(f, "resource {} is not a {expected}", index.index)
+            }
+            FrameGraphError::MissingRenderPipeline { id } => {
+                write!(f, "render pipeline {id} is not available")
+            }
+            FrameGraphError::MissingComputePipeline { id } => {
+                write!(f, "compute pipeline {id} is not available")
+            }
+            FrameGraphError::Pass { pass, source } => write!(f, "pass `{pass}` failed: {source}"),
+            FrameGraphError::Execute { errors } => {
+                write!(f, "{} pass(es) failed:", errors.len())?;
+
+                for error in errors.iter() {
+                    write!(f, "\n  {error}")?;
+                }
+
+                Ok(())
+            }
+            FrameGraphError::NotCompiled => {
+                write!(f, "frame graph was executed without being compiled")
+            }
+        }
+    }
+}
+
+impl core::error::Error for FrameGraphError {}
+
+impl From<CompileError> for FrameGraphError {
+    fn from(value: CompileError) -> Self {
+        FrameGraphError::Compile(value)
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use wgpu::{BindGroupLayout as WgpuBindGroupLayout, BindGroupLayoutEntry};
 
 use crate::define_atomic_id;
@@ -9,15 +11,32 @@ pub struct BindGroupLayoutDescriptor {
 }
 
 #[derive(Clone, Debug)]
-pub struct GpuBindGroupLayout(WgpuBindGroupLayout);
+pub struct GpuBindGroupLayout {
+    value: WgpuBindGroupLayout,
+    entries: Arc<[BindGroupLayoutEntry]>,
+}
 
 impl GpuBindGroupLayout {
     pub fn new(value: WgpuBindGroupLayout) -> Self {
-        GpuBindGroupLayout(value)
+        GpuBindGroupLayout {
+            value,
+            entries: Arc::new([]),
+        }
+    }
+
+    /// Records the entries the layout was created from, which lets bind groups
+    /// infer the usages their resources need.
+    pub fn with_entries(mut self, entries: Vec<BindGroupLayoutEntry>) -> Self {
+        self.entries = entries.into();
+        self
+    }
+
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        &self.entries
     }
 
     pub(crate) fn get_wgpu_bind_group_layout(&self) -> &WgpuBindGroupLayout {
-        &self.0
+        &self.value
     }
 }
 
@@ -44,4 +63,14 @@ impl BindGroupLayout {
     pub fn id(&self) -> BindGroupLayoutId {
         self.id
     }
+
+    /// The entries the layout was created from. Empty for layouts not created
+    /// through [`RenderDevice`](crate::gfx_base::RenderDevice).
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        self.value.entries()
+    }
+
+    pub fn get_entry(&self, binding: u32) -> Option<&BindGroupLayoutEntry> {
+        self.entries().iter().find(|entry| entry.binding == binding)
+    }
 }
@@ -4,7 +4,7 @@ use wgpu::{
     Buffer as WgpuBuffer, BufferAddress, BufferDescriptor as WgpuBufferDescriptor, BufferUsages,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct GpuBuffer(WgpuBuffer);
 
 impl GpuBuffer {
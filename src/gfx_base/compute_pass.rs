@@ -0,0 +1,26 @@
+use wgpu::ComputePassDescriptor as WgpuComputePassDescriptor;
+
+#[derive(Default)]
+pub struct ComputePassDescriptor {
+    pub label: Option<String>,
+}
+
+pub struct GpuComputePass(wgpu::ComputePass<'static>);
+
+impl GpuComputePass {
+    pub(crate) fn get_compute_pass_mut(&mut self) -> &mut wgpu::ComputePass<'static> {
+        &mut self.0
+    }
+
+    pub fn begin_compute_pass(
+        command_encoder: &mut wgpu::CommandEncoder,
+        desc: &ComputePassDescriptor,
+    ) -> Self {
+        let compute_pass = command_encoder.begin_compute_pass(&WgpuComputePassDescriptor {
+            label: desc.label.as_deref(),
+            timestamp_writes: None,
+        });
+
+        GpuComputePass(compute_pass.forget_lifetime())
+    }
+}
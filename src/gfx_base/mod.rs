@@ -2,8 +2,10 @@ mod bind_group;
 mod bind_group_layout;
 mod buffer;
 mod command_encoder;
+mod compute_pass;
 mod pipeline;
 mod pipeline_layout;
+mod recorder;
 mod render_device;
 mod render_pass;
 mod resource_macros;
@@ -17,8 +19,10 @@ pub use bind_group::*;
 pub use bind_group_layout::*;
 pub use buffer::*;
 pub use command_encoder::*;
+pub use compute_pass::*;
 pub use pipeline::*;
 pub use pipeline_layout::*;
+pub use recorder::*;
 pub use render_device::*;
 pub use render_pass::*;
 pub use sampler::*;
@@ -29,7 +33,7 @@ pub use texture_view::*;
 
 pub use wgpu::{ShaderModuleDescriptor, ShaderSource};
 
-use wgpu::{Instance, Queue, SurfaceTargetUnsafe};
+use wgpu::{CommandBuffer, Instance, Queue, SurfaceTargetUnsafe};
 
 use std::sync::Arc;
 
@@ -40,6 +44,10 @@ impl RenderQueue {
     pub fn new(queue: Queue) -> Self {
         Self(Arc::new(queue))
     }
+
+    pub fn submit<I: IntoIterator<Item = CommandBuffer>>(&self, command_buffers: I) {
+        self.0.submit(command_buffers);
+    }
 }
 
 pub struct RenderInstance(pub Arc<Instance>);
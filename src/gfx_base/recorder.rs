@@ -0,0 +1,50 @@
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenderDeviceEvent {
+    CreateBuffer {
+        label: Option<String>,
+        size: u64,
+    },
+    CreateTexture {
+        label: Option<String>,
+    },
+    CreateCommandEncoder {
+        label: Option<String>,
+    },
+    CreateBindGroup {
+        label: Option<String>,
+    },
+    /// A transient resource was served from the cache instead of being created.
+    ReuseResource {
+        index: usize,
+    },
+    ReleaseResource {
+        index: usize,
+    },
+    ExecutePass {
+        name: String,
+    },
+    Submit {
+        command_buffers: usize,
+    },
+    Present,
+}
+
+/// Shared log of what a [`RenderDevice`](super::RenderDevice) was asked to do.
+#[derive(Clone, Default, Debug)]
+pub struct RenderDeviceRecorder(Arc<Mutex<Vec<RenderDeviceEvent>>>);
+
+impl RenderDeviceRecorder {
+    pub fn record(&self, event: RenderDeviceEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+
+    pub fn events(&self) -> Vec<RenderDeviceEvent> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn take_events(&self) -> Vec<RenderDeviceEvent> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
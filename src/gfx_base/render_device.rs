@@ -10,18 +10,35 @@ use crate::gfx_base::{
 use super::{
     BindGroupDescriptor, BindGroupLayoutDescriptor, BindingResource, BufferDescriptor,
     CommandEncoderDescriptor, GpuBindGroup, GpuBindGroupLayout, GpuBindingResource, GpuBuffer,
-    GpuSampler, GpuSurface, GpuTexture, GpuTextureView, RenderPipelineDescriptor,
-    SamplerDescriptor, TextureDescriptor,
+    GpuSampler, GpuSurface, GpuTexture, GpuTextureView, RenderDeviceEvent, RenderDeviceRecorder,
+    RenderPipelineDescriptor, SamplerDescriptor, TextureDescriptor,
 };
 
 #[derive(Debug, Clone)]
 pub struct RenderDevice {
     device: Device,
+    recorder: Option<RenderDeviceRecorder>,
 }
 
 impl RenderDevice {
     pub fn new(device: Device) -> Self {
-        Self { device }
+        Self {
+            device,
+            recorder: None,
+        }
+    }
+
+    /// Logs resource, encoder and pass activity into `recorder`. Mostly useful with
+    /// wgpu's noop backend to test graphs without a GPU.
+    pub fn with_recorder(mut self, recorder: RenderDeviceRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn record(&self, event: RenderDeviceEvent) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(event);
+        }
     }
 
     pub fn configure_surface(&self, surface: &GpuSurface, config: &SurfaceConfiguration) {
@@ -95,6 +112,10 @@ impl RenderDevice {
     }
 
     pub fn create_bind_group(&self, desc: &BindGroupDescriptor) -> GpuBindGroup {
+        self.record(RenderDeviceEvent::CreateBindGroup {
+            label: desc.label.clone(),
+        });
+
         let entries = desc
             .entries
             .iter()
@@ -162,19 +183,29 @@ impl RenderDevice {
                     label: None,
                     entries: &entries,
                 });
-        GpuBindGroupLayout::new(bind_group_layout)
+        GpuBindGroupLayout::new(bind_group_layout).with_entries(entries)
     }
 
     pub fn create_command_encoder(&self, desc: &CommandEncoderDescriptor) -> CommandEncoder {
+        self.record(RenderDeviceEvent::CreateCommandEncoder {
+            label: desc.label.clone(),
+        });
         self.device.create_command_encoder(&desc.get_buffer_desc())
     }
 
     pub fn create_texture(&self, desc: &TextureDescriptor) -> GpuTexture {
+        self.record(RenderDeviceEvent::CreateTexture {
+            label: desc.label.clone(),
+        });
         let texture = self.device.create_texture(&desc.get_wgpu_desc());
         GpuTexture::new(texture)
     }
 
     pub fn create_buffer(&self, desc: &BufferDescriptor) -> GpuBuffer {
+        self.record(RenderDeviceEvent::CreateBuffer {
+            label: desc.label.as_ref().map(ToString::to_string),
+            size: desc.size,
+        });
         let buffer = self.device.create_buffer(&desc.get_wgpu_desc());
         GpuBuffer::new(buffer)
     }
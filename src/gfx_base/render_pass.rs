@@ -17,8 +17,8 @@ impl RenderPassDepthStencilAttachment {
     ) -> WgpuRenderPassDepthStencilAttachment<'_> {
         WgpuRenderPassDepthStencilAttachment {
             view: self.view.get_wgpu_texture_view(),
-            depth_ops: self.depth_ops.clone(),
-            stencil_ops: self.stencil_ops.clone(),
+            depth_ops: self.depth_ops,
+            stencil_ops: self.stencil_ops,
         }
     }
 }
@@ -34,12 +34,12 @@ impl RenderPassColorAttachment {
     fn get_wgpu_render_pass_color_attachment(&self) -> WgpuRenderPassColorAttachment<'_> {
         WgpuRenderPassColorAttachment {
             view: self.view.get_wgpu_texture_view(),
-            depth_slice: self.depth_slice.clone(),
+            depth_slice: self.depth_slice,
             resolve_target: self
                 .resolve_target
                 .as_ref()
                 .map(|resolve_target| resolve_target.get_wgpu_texture_view()),
-            ops: self.ops.clone(),
+            ops: self.ops,
         }
     }
 }
@@ -5,19 +5,40 @@ use wgpu::{
 
 use super::{GpuTextureView, TextureViewDescriptor};
 
-pub struct GpuSurfaceTexture(WgpuSurfaceTexture);
+pub struct GpuSurfaceTexture {
+    texture: GpuTexture,
+    surface_texture: Option<WgpuSurfaceTexture>,
+}
 
 impl GpuSurfaceTexture {
     pub(crate) fn new(value: WgpuSurfaceTexture) -> Self {
-        GpuSurfaceTexture(value)
+        GpuSurfaceTexture {
+            texture: GpuTexture::new(value.texture.clone()),
+            surface_texture: Some(value),
+        }
+    }
+
+    /// Stands in for a swapchain image when rendering offscreen, such as in headless
+    /// runs, so the same graph can target either. Presenting it does nothing.
+    pub fn from_texture(texture: GpuTexture) -> Self {
+        GpuSurfaceTexture {
+            texture,
+            surface_texture: None,
+        }
     }
 
     pub fn present(self) {
-        self.0.present();
+        if let Some(surface_texture) = self.surface_texture {
+            surface_texture.present();
+        }
+    }
+
+    pub fn texture(&self) -> GpuTexture {
+        self.texture.clone()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GpuTexture(WgpuTexture);
 
 impl GpuTexture {
@@ -25,6 +46,10 @@ impl GpuTexture {
         Self(texture)
     }
 
+    pub(crate) fn get_wgpu_texture(&self) -> &WgpuTexture {
+        &self.0
+    }
+
     pub fn create_view(&self, desc: &TextureViewDescriptor) -> GpuTextureView {
         GpuTextureView::new(self.0.create_view(&desc.get_desc()))
     }
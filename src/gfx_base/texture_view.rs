@@ -3,7 +3,7 @@ use wgpu::{
     TextureViewDescriptor as WgpuTextureViewDescriptor, TextureViewDimension,
 };
 
-#[derive(Default, Clone, Debug, PartialEq, Eq)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TextureViewDescriptor {
     pub label: Option<String>,
     pub format: Option<TextureFormat>,
@@ -32,7 +32,7 @@ impl TextureViewDescriptor {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct GpuTextureView(WgpuTextureView);
 
 impl GpuTextureView {
@@ -0,0 +1,165 @@
+use crate::{AnyTransientResourceDescriptor, FrameGraph, VirtualResource};
+
+/// Transient memory of a compiled graph, estimated from resource descriptors.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransientMemoryReport {
+    /// Bytes needed if every transient resource gets its own allocation.
+    pub peak_without_aliasing: u64,
+    /// Bytes needed once resources with disjoint lifetimes share slots.
+    pub peak_with_aliasing: u64,
+    /// Number of transient resources the graph uses.
+    pub resource_count: usize,
+    /// Number of physical slots they were packed into.
+    pub slot_count: usize,
+}
+
+struct AliasSlot {
+    desc: AnyTransientResourceDescriptor,
+    last_position: usize,
+}
+
+/// Whether a resource created with `slot` can stand in for one created with `desc`.
+/// Buffers may be larger than requested, everything else must match except labels.
+fn can_alias(slot: &AnyTransientResourceDescriptor, desc: &AnyTransientResourceDescriptor) -> bool {
+    match (slot, desc) {
+        (
+            AnyTransientResourceDescriptor::Buffer(slot),
+            AnyTransientResourceDescriptor::Buffer(desc),
+        ) => {
+            slot.usage == desc.usage
+                && !slot.mapped_at_creation
+                && !desc.mapped_at_creation
+                && slot.size >= desc.size
+        }
+        (
+            AnyTransientResourceDescriptor::Texture(slot),
+            AnyTransientResourceDescriptor::Texture(desc),
+        ) => {
+            slot.size == desc.size
+                && slot.mip_level_count == desc.mip_level_count
+                && slot.sample_count == desc.sample_count
+                && slot.dimension == desc.dimension
+                && slot.format == desc.format
+                && slot.usage == desc.usage
+                && slot.view_formats == desc.view_formats
+        }
+        _ => false,
+    }
+}
+
+/// Whether `slot` can be grown to serve `desc`, which only buffers allow.
+fn can_grow(slot: &AnyTransientResourceDescriptor, desc: &AnyTransientResourceDescriptor) -> bool {
+    match (slot, desc) {
+        (
+            AnyTransientResourceDescriptor::Buffer(slot),
+            AnyTransientResourceDescriptor::Buffer(desc),
+        ) => slot.usage == desc.usage && !slot.mapped_at_creation && !desc.mapped_at_creation,
+        _ => false,
+    }
+}
+
+impl FrameGraph {
+    /// Packs transient resources whose lifetimes don't overlap into shared slots,
+    /// walking them in order of first use and picking the smallest free slot that
+    /// fits, or else growing a free buffer slot. With aliasing enabled, each resource
+    /// is then requested with its slot's descriptor, so the release of one lets the
+    /// cache hand the same allocation to the next.
+    pub(crate) fn plan_aliasing(&mut self) -> TransientMemoryReport {
+        let mut positions = vec![0; self.pass_nodes.len()];
+
+        for (position, handle) in self.execution_order.iter().enumerate() {
+            positions[handle.index] = position;
+        }
+
+        let mut resources = self
+            .resource_nodes
+            .iter()
+            .filter_map(|resource_node| {
+                let VirtualResource::Setuped(desc) = &resource_node.resource else {
+                    return None;
+                };
+
+                if resource_node.exported {
+                    return None;
+                }
+
+                let first = positions[resource_node.first_use_pass?.index];
+                let last = positions[resource_node.last_user_pass?.index];
+
+                Some((resource_node.index.index, first, last, desc.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        resources.sort_by_key(|(index, first, _, _)| (*first, *index));
+
+        let mut report = TransientMemoryReport {
+            resource_count: resources.len(),
+            ..Default::default()
+        };
+        let mut slots: Vec<AliasSlot> = vec![];
+        let mut assignments = vec![];
+
+        for (index, first, last, desc) in resources {
+            report.peak_without_aliasing += desc.estimated_memory_size();
+
+            let free = |slot: &&AliasSlot| slot.last_position < first;
+
+            let fitting = slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| free(slot) && can_alias(&slot.desc, &desc))
+                .min_by_key(|(_, slot)| slot.desc.estimated_memory_size())
+                .map(|(slot_index, _)| slot_index);
+
+            let growable = || {
+                slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| free(slot) && can_grow(&slot.desc, &desc))
+                    .max_by_key(|(_, slot)| slot.desc.estimated_memory_size())
+                    .map(|(slot_index, _)| slot_index)
+            };
+
+            let slot_index = match fitting.or_else(growable) {
+                Some(slot_index) => {
+                    let slot = &mut slots[slot_index];
+
+                    if let (
+                        AnyTransientResourceDescriptor::Buffer(slot_desc),
+                        AnyTransientResourceDescriptor::Buffer(desc),
+                    ) = (&mut slot.desc, &desc)
+                    {
+                        slot_desc.size = slot_desc.size.max(desc.size);
+                    }
+
+                    slot.last_position = last;
+                    slot_index
+                }
+                None => {
+                    slots.push(AliasSlot {
+                        desc,
+                        last_position: last,
+                    });
+                    slots.len() - 1
+                }
+            };
+
+            assignments.push((index, slot_index));
+        }
+
+        report.slot_count = slots.len();
+        report.peak_with_aliasing = slots
+            .iter()
+            .map(|slot| slot.desc.estimated_memory_size())
+            .sum();
+
+        if self.memory_aliasing {
+            for (index, slot_index) in assignments {
+                self.resource_nodes[index].resource =
+                    VirtualResource::Setuped(slots[slot_index].desc.clone());
+            }
+        }
+
+        report
+    }
+}
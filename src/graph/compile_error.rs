@@ -0,0 +1,115 @@
+use core::fmt::{Display, Formatter, Result};
+
+use wgpu::TextureFormat;
+
+use crate::ResourceUsage;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileDiagnostic {
+    /// A transient resource is read before any pass wrote the requested version.
+    ReadUninitialized {
+        pass: String,
+        resource: String,
+        version: u32,
+    },
+    /// A pass reads a version that an earlier registered pass already overwrote.
+    ReadStaleVersion {
+        pass: String,
+        resource: String,
+        version: u32,
+        latest_version: u32,
+    },
+    /// Two passes claim to produce the same version of a resource.
+    DuplicateWrite {
+        first_pass: String,
+        second_pass: String,
+        resource: String,
+        version: u32,
+    },
+    /// A pass uses an imported resource in a way it was not created for. Imported
+    /// resources already exist, so their usage flags can't be extended.
+    MissingImportedUsage {
+        pass: String,
+        resource: String,
+        usage: ResourceUsage,
+    },
+    /// A render pass uses a view as its depth-stencil attachment whose format has
+    /// neither a depth nor a stencil aspect.
+    InvalidDepthStencilFormat { pass: String, format: TextureFormat },
+}
+
+impl Display for CompileDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            CompileDiagnostic::ReadUninitialized {
+                pass,
+                resource,
+                version,
+            } => write!(
+                f,
+                "pass `{pass}` reads version {version} of `{resource}`, which no pass writes"
+            ),
+            CompileDiagnostic::ReadStaleVersion {
+                pass,
+                resource,
+                version,
+                latest_version,
+            } => write!(
+                f,
+                "pass `{pass}` reads version {version} of `{resource}`, but version {latest_version} was already written"
+            ),
+            CompileDiagnostic::DuplicateWrite {
+                first_pass,
+                second_pass,
+                resource,
+                version,
+            } => write!(
+                f,
+                "passes `{first_pass}` and `{second_pass}` both write version {version} of `{resource}`"
+            ),
+            CompileDiagnostic::MissingImportedUsage {
+                pass,
+                resource,
+                usage,
+            } => write!(
+                f,
+                "pass `{pass}` needs {usage} on imported `{resource}`, which was not created with it"
+            ),
+            CompileDiagnostic::InvalidDepthStencilFormat { pass, format } => write!(
+                f,
+                "pass `{pass}` uses a {format:?} view as depth-stencil attachment, which is not a depth or stencil format"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    /// The named passes depend on each other and can't be ordered.
+    Cycle { passes: Vec<String> },
+    /// The graph reads or writes resource versions inconsistently, or uses imported
+    /// resources in ways they don't support.
+    Invalid { diagnostics: Vec<CompileDiagnostic> },
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            CompileError::Cycle { passes } => {
+                write!(f, "frame graph has a dependency cycle between passes: ")?;
+                write!(f, "{}", passes.join(", "))
+            }
+            CompileError::Invalid { diagnostics } => {
+                write!(f, "frame graph is invalid:")?;
+
+                for diagnostic in diagnostics.iter() {
+                    write!(f, "\n  {diagnostic}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for CompileError {}
@@ -0,0 +1,226 @@
+use core::fmt::{Display, Formatter, Result};
+use std::collections::BTreeMap;
+
+use crate::{FrameGraph, GraphRawResourceHandle};
+
+#[derive(Clone)]
+struct DotPass {
+    name: String,
+    reads: Vec<GraphRawResourceHandle>,
+    writes: Vec<GraphRawResourceHandle>,
+    culled: bool,
+    has_side_effect: bool,
+    order: Option<usize>,
+}
+
+#[derive(Clone)]
+struct DotResource {
+    name: String,
+    imported: bool,
+    history: bool,
+    exported: bool,
+    first_use_pass: Option<usize>,
+    last_user_pass: Option<usize>,
+}
+
+/// Snapshot of the graph structure, rendered in Graphviz DOT syntax.
+///
+/// Passes are boxes, resource versions are ellipses grouped by resource. Culled
+/// passes are dashed and grey, side-effect passes have a double border, and
+/// imported, history and exported resources are filled in different colors. Each
+/// resource cluster is labelled with the passes that bound its lifetime.
+#[derive(Clone)]
+pub(crate) struct DotGraph {
+    passes: Vec<DotPass>,
+    resources: Vec<DotResource>,
+}
+
+impl DotGraph {
+    pub(crate) fn new(frame_graph: &FrameGraph) -> Self {
+        let mut orders = vec![None; frame_graph.pass_nodes.len()];
+
+        for (order, handle) in frame_graph.execution_order.iter().enumerate() {
+            orders[handle.index] = Some(order);
+        }
+
+        let passes = frame_graph
+            .pass_nodes
+            .iter()
+            .map(|pass_node| DotPass {
+                name: pass_node.name.clone(),
+                reads: pass_node.reads.clone(),
+                writes: pass_node.writes.clone(),
+                culled: pass_node.culled,
+                has_side_effect: pass_node.has_side_effect,
+                order: orders[pass_node.index.index],
+            })
+            .collect();
+
+        let resources = frame_graph
+            .resource_nodes
+            .iter()
+            .map(|resource_node| DotResource {
+                name: resource_node.name.clone(),
+                imported: resource_node.is_imported(),
+                history: resource_node.is_history(),
+                exported: resource_node.exported,
+                first_use_pass: resource_node.first_use_pass.map(|handle| handle.index),
+                last_user_pass: resource_node.last_user_pass.map(|handle| handle.index),
+            })
+            .collect();
+
+        Self { passes, resources }
+    }
+
+    /// Every version of each resource, labelled with the sub-resources written.
+    fn versions(&self) -> Vec<BTreeMap<u32, String>> {
+        let mut versions = vec![BTreeMap::new(); self.resources.len()];
+
+        for pass in self.passes.iter() {
+            for handle in pass.reads.iter() {
+                versions[handle.index.index]
+                    .entry(handle.version)
+                    .or_insert_with(|| format!("v{}", handle.version));
+            }
+
+            for handle in pass.writes.iter() {
+                versions[handle.index.index].insert(handle.version, version_label(handle));
+            }
+        }
+
+        versions
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn resource_id(handle: &GraphRawResourceHandle) -> String {
+    format!("r{}_v{}", handle.index.index, handle.version)
+}
+
+fn version_label(handle: &GraphRawResourceHandle) -> String {
+    if handle.subresource.is_full() {
+        format!("v{}", handle.version)
+    } else {
+        format!("v{}\\n{}", handle.version, handle.subresource)
+    }
+}
+
+fn edge_attributes(handle: &GraphRawResourceHandle, culled: bool) -> String {
+    let mut attributes = vec![];
+
+    if !handle.subresource.is_full() {
+        attributes.push(format!("label=\"{}\"", handle.subresource));
+    }
+
+    if culled {
+        attributes.push("style=dashed".to_string());
+    }
+
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "digraph FrameGraph {{")?;
+        writeln!(f, "    rankdir=LR;")?;
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            let label = match pass.order {
+                Some(order) => format!("#{order} {}", escape(&pass.name)),
+                None => escape(&pass.name),
+            };
+
+            let mut attributes = vec![format!("label=\"{label}\""), "shape=box".to_string()];
+
+            if pass.culled {
+                attributes.push("style=dashed".to_string());
+                attributes.push("color=grey".to_string());
+                attributes.push("fontcolor=grey".to_string());
+            }
+
+            if pass.has_side_effect {
+                attributes.push("peripheries=2".to_string());
+            }
+
+            writeln!(f, "    p{index} [{}];", attributes.join(", "))?;
+        }
+
+        for (index, (resource, versions)) in self.resources.iter().zip(self.versions()).enumerate()
+        {
+            if versions.is_empty() {
+                continue;
+            }
+
+            let lifetime = match (resource.first_use_pass, resource.last_user_pass) {
+                (Some(first), Some(last)) => format!(
+                    "\\n{} .. {}",
+                    escape(&self.passes[first].name),
+                    escape(&self.passes[last].name)
+                ),
+                _ => String::new(),
+            };
+
+            writeln!(f, "    subgraph cluster_r{index} {{")?;
+            writeln!(f, "        label=\"{}{lifetime}\";", escape(&resource.name))?;
+
+            if resource.imported {
+                writeln!(f, "        style=filled;")?;
+                writeln!(f, "        color=lightblue;")?;
+            } else if resource.history {
+                writeln!(f, "        style=filled;")?;
+                writeln!(f, "        color=palegreen;")?;
+            } else if resource.exported {
+                writeln!(f, "        style=filled;")?;
+                writeln!(f, "        color=khaki;")?;
+            } else {
+                writeln!(f, "        style=rounded;")?;
+            }
+
+            for (version, label) in versions {
+                writeln!(
+                    f,
+                    "        r{index}_v{version} [label=\"{label}\", shape=ellipse];"
+                )?;
+            }
+
+            writeln!(f, "    }}")?;
+        }
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            for handle in pass.reads.iter() {
+                writeln!(
+                    f,
+                    "    {} -> p{index}{};",
+                    resource_id(handle),
+                    edge_attributes(handle, pass.culled)
+                )?;
+            }
+
+            for handle in pass.writes.iter() {
+                writeln!(
+                    f,
+                    "    p{index} -> {}{};",
+                    resource_id(handle),
+                    edge_attributes(handle, pass.culled)
+                )?;
+            }
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+impl FrameGraph {
+    /// Renders the passes and resource versions in Graphviz DOT syntax. Culling,
+    /// execution order and lifetimes are only shown once the graph is compiled.
+    pub fn to_dot(&self) -> String {
+        DotGraph::new(self).to_string()
+    }
+}
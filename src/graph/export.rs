@@ -0,0 +1,39 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{
+    FrameGraph, FrameGraphContext, Handle, IndexHandle, IntoArcTransientResource, ResourceNode,
+};
+
+/// Token for an exported resource, redeemed with
+/// [`FrameGraphContext::take_exported`] once the graph has executed.
+pub struct ExportedResource<ResourceType> {
+    index: IndexHandle<ResourceNode>,
+    _marker: PhantomData<ResourceType>,
+}
+
+impl FrameGraph {
+    /// Marks a resource as exported. Passes writing it are never culled, and when its
+    /// last pass releases it, it is handed to the caller instead of going back to the
+    /// cache, so it can be imported again in a later frame.
+    pub fn export<ResourceType: IntoArcTransientResource>(
+        &mut self,
+        handle: &Handle<ResourceType>,
+    ) -> ExportedResource<ResourceType> {
+        self.get_resource_node_mut(&handle.raw.index).exported = true;
+
+        ExportedResource {
+            index: handle.raw.index,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl FrameGraphContext<'_> {
+    /// Takes an exported resource. Returns `None` if no pass used it.
+    pub fn take_exported<ResourceType: IntoArcTransientResource>(
+        &mut self,
+        exported: &ExportedResource<ResourceType>,
+    ) -> Option<Arc<ResourceType>> {
+        self.resource_table.take_exported_resource(exported.index)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    AnyTransientResourceDescriptor, FrameGraph, Handle, IndexHandle, ResourceNode,
+    TransientResource, TransientResourceDescriptor, TypeEquals, VirtualResource,
+};
+
+/// Both halves of a history resource.
+pub struct HistoryHandles<ResourceType: TransientResource> {
+    /// This frame's resource, to be written.
+    pub current: Handle<ResourceType>,
+    /// What `current` held at the end of last frame.
+    pub previous: Handle<ResourceType>,
+}
+
+impl FrameGraph {
+    /// Creates a resource that persists across frames, backed by a pair of physical
+    /// resources in [`TransientResourceCache`](crate::TransientResourceCache) that
+    /// swap in its `end_frame` after each frame that uses them. On the first frame, or
+    /// after `desc` changes, both are created fresh and `previous` holds no useful
+    /// contents.
+    pub fn create_history<DescriptorType>(&mut self, name: &str, desc: DescriptorType) -> HistoryHandles<DescriptorType::Resource>
+    where
+        DescriptorType: TransientResourceDescriptor
+            + TypeEquals<
+                Other = <<DescriptorType as TransientResourceDescriptor>::Resource as TransientResource>::Descriptor,
+            >,
+    {
+        let desc: AnyTransientResourceDescriptor = desc.into();
+
+        let current = self.add_resource_node(
+            name,
+            VirtualResource::History {
+                key: name.to_string(),
+                desc: desc.clone(),
+                previous: false,
+            },
+        );
+        let previous = self.add_resource_node(
+            &format!("{name}.previous"),
+            VirtualResource::History {
+                key: name.to_string(),
+                desc,
+                previous: true,
+            },
+        );
+
+        self.insert(name, current.raw.index);
+
+        HistoryHandles { current, previous }
+    }
+
+    fn add_resource_node<ResourceType: TransientResource>(
+        &mut self,
+        name: &str,
+        virtual_resource: VirtualResource,
+    ) -> Handle<ResourceType> {
+        let resource_node_handle = IndexHandle::new(self.resource_nodes.len());
+        let resource_node = ResourceNode::new(name, resource_node_handle, virtual_resource);
+
+        let handle = resource_node.get_handle();
+
+        self.resource_nodes.push(resource_node);
+
+        handle
+    }
+}
@@ -1,18 +1,44 @@
+mod aliasing;
+mod compile_error;
+mod dot;
+mod export;
+mod history;
+mod parallel;
 mod pass_node_builder;
-
+mod retained;
+mod subresource;
+mod surface;
+
+pub use aliasing::TransientMemoryReport;
+pub use compile_error::*;
+pub use export::ExportedResource;
+pub use history::HistoryHandles;
+pub use parallel::RecordingThreadPool;
 pub use pass_node_builder::*;
 
-use std::sync::Arc;
+use dot::DotGraph;
+use retained::RetainedFrameGraph;
+use subresource::SubresourceTracker;
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    mem::take,
+    sync::Arc,
+};
 use wgpu::CommandBuffer;
 
 use crate::{
-    ResourceTable, TransientResourceCache,
-    gfx_base::{PipelineContainer, RenderDevice},
+    AnyTransientResourceDescriptor, FrameGraphError, ResourceTable, TransientResourceCache,
+    gfx_base::{
+        GpuSurfaceTexture, PipelineContainer, RenderDevice, RenderDeviceEvent, RenderQueue,
+    },
 };
 
 use super::{
-    DevicePass, Handle, IndexHandle, IntoArcTransientResource, PassNode, ResourceBoard,
-    ResourceNode, TransientResource, TransientResourceDescriptor, TypeEquals, VirtualResource,
+    DevicePass, GraphRawResourceHandle, Handle, IndexHandle, IntoArcTransientResource, PassNode,
+    ResourceBoard, ResourceNode, TransientResource, TransientResourceDescriptor, TypeEquals,
+    VirtualResource,
 };
 
 pub struct FrameGraphContext<'a> {
@@ -21,6 +47,10 @@ pub struct FrameGraphContext<'a> {
     pub render_device: RenderDevice,
     pub transient_resource_cache: &'a mut TransientResourceCache,
     pub(crate) command_buffers: Vec<CommandBuffer>,
+    pub(crate) recording_pool: Option<Arc<RecordingThreadPool>>,
+    pub(crate) render_queue: Option<RenderQueue>,
+    pub(crate) submit_batch_size: Option<usize>,
+    pub(crate) passes_since_submit: usize,
 }
 
 impl<'a> FrameGraphContext<'a> {
@@ -35,9 +65,69 @@ impl<'a> FrameGraphContext<'a> {
             render_device: render_device.clone(),
             transient_resource_cache,
             command_buffers: vec![],
+            recording_pool: None,
+            render_queue: None,
+            submit_batch_size: None,
+            passes_since_submit: 0,
+        }
+    }
+
+    pub fn with_render_queue(mut self, render_queue: RenderQueue) -> Self {
+        self.render_queue = Some(render_queue);
+        self
+    }
+
+    /// With a render queue, submits the recorded command buffers every `passes`
+    /// executed passes, so the GPU can start before the whole frame is recorded.
+    /// Parallel recording leaves every command buffer to `submit` or `finish`.
+    pub fn with_submit_batch_size(mut self, passes: usize) -> Self {
+        self.submit_batch_size = Some(passes.max(1));
+        self
+    }
+
+    /// Submits the command buffers recorded so far. Does nothing without a render
+    /// queue, in which case they are left for `finish`.
+    pub fn submit(&mut self) {
+        self.passes_since_submit = 0;
+
+        let Some(render_queue) = &self.render_queue else {
+            return;
+        };
+
+        let command_buffers = take(&mut self.command_buffers);
+
+        self.render_device.record(RenderDeviceEvent::Submit {
+            command_buffers: command_buffers.len(),
+        });
+        render_queue.submit(command_buffers);
+    }
+
+    pub fn submit_and_present(&mut self, surface_texture: GpuSurfaceTexture) {
+        self.submit();
+
+        self.render_device.record(RenderDeviceEvent::Present);
+        surface_texture.present();
+    }
+
+    pub(crate) fn pass_executed(&mut self) {
+        self.passes_since_submit += 1;
+
+        if self
+            .submit_batch_size
+            .is_some_and(|submit_batch_size| self.passes_since_submit >= submit_batch_size)
+        {
+            self.submit();
         }
     }
 
+    /// Records passes on the threads of `recording_pool`. Resources are still
+    /// requested and released on the calling thread, and `finish` still returns the
+    /// command buffers in execution order.
+    pub fn with_recording_pool(mut self, recording_pool: Arc<RecordingThreadPool>) -> Self {
+        self.recording_pool = Some(recording_pool);
+        self
+    }
+
     pub fn add_command_buffer(&mut self, command_buffer: CommandBuffer) {
         self.command_buffers.push(command_buffer);
     }
@@ -55,12 +145,48 @@ pub trait ResourceMaterial {
 
 pub struct CompiledFrameGraph {
     device_passes: Vec<DevicePass>,
+    execution_order: Vec<IndexHandle<PassNode>>,
+    dot_graph: DotGraph,
+    memory_report: TransientMemoryReport,
+    reused: bool,
 }
 
 impl CompiledFrameGraph {
-    pub fn execute(&self, context: &mut FrameGraphContext) {
+    pub fn memory_report(&self) -> &TransientMemoryReport {
+        &self.memory_report
+    }
+
+    /// Whether this was carried over from the previous frame instead of compiled.
+    pub fn is_reused(&self) -> bool {
+        self.reused
+    }
+
+    /// Renders the graph this was compiled from in Graphviz DOT syntax.
+    pub fn to_dot(&self) -> String {
+        self.dot_graph.to_string()
+    }
+
+    /// Executes every pass. A pass that fails is skipped and the rest still run;
+    /// their errors are returned together.
+    pub fn execute(&self, context: &mut FrameGraphContext) -> Result<(), FrameGraphError> {
+        if let Some(recording_pool) = context.recording_pool.clone() {
+            return self.execute_parallel(context, &recording_pool);
+        }
+
+        let mut errors = vec![];
+
         for device_pass in self.device_passes.iter() {
-            device_pass.execute(context);
+            if let Err(error) = device_pass.execute(context) {
+                errors.push(error);
+            }
+
+            context.pass_executed();
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FrameGraphError::Execute { errors })
         }
     }
 }
@@ -71,30 +197,374 @@ pub struct FrameGraph {
     pub(crate) pass_nodes: Vec<PassNode>,
     pub(crate) compiled_frame_graph: Option<CompiledFrameGraph>,
     pub(crate) resource_board: ResourceBoard,
+    pub(crate) execution_order: Vec<IndexHandle<PassNode>>,
+    pub(crate) retained: bool,
+    pub(crate) memory_aliasing: bool,
+    pub(crate) structure_hash: Option<u64>,
+    pub(crate) retained_frame_graph: Option<RetainedFrameGraph>,
+    pub(crate) builder_diagnostics: Vec<CompileDiagnostic>,
 }
 
 impl FrameGraph {
+    /// In retained mode the compiled graph survives `reset`, and the next `compile`
+    /// reuses it if the graph structure is unchanged.
+    pub fn set_retained(&mut self, retained: bool) {
+        self.retained = retained;
+
+        if !retained {
+            self.retained_frame_graph = None;
+        }
+    }
+
+    pub fn is_retained(&self) -> bool {
+        self.retained
+    }
+
+    /// With memory aliasing, transient resources whose lifetimes don't overlap share
+    /// one allocation, so a buffer may be larger than its descriptor asked for.
+    /// Commands that use the whole buffer, such as copies without a size, see the
+    /// larger size.
+    pub fn set_memory_aliasing(&mut self, memory_aliasing: bool) {
+        self.memory_aliasing = memory_aliasing;
+        self.retained_frame_graph = None;
+    }
+
+    pub fn is_memory_aliasing(&self) -> bool {
+        self.memory_aliasing
+    }
+
     pub fn reset(&mut self) {
         self.pass_nodes = vec![];
         self.resource_nodes = vec![];
-        self.compiled_frame_graph = None;
         self.resource_board = ResourceBoard::default();
+        self.execution_order = vec![];
+        self.builder_diagnostics = vec![];
+
+        let compiled_frame_graph = self.compiled_frame_graph.take();
+        let structure_hash = self.structure_hash.take();
+
+        self.retained_frame_graph = match (self.retained, structure_hash, compiled_frame_graph) {
+            (true, Some(structure_hash), Some(compiled_frame_graph)) => Some(RetainedFrameGraph {
+                structure_hash,
+                compiled_frame_graph,
+            }),
+            _ => None,
+        };
     }
 
-    pub fn execute(&mut self, context: &mut FrameGraphContext) {
-        if self.compiled_frame_graph.is_none() {
-            return;
+    pub fn compiled_frame_graph(&self) -> Option<&CompiledFrameGraph> {
+        self.compiled_frame_graph.as_ref()
+    }
+
+    /// Executes the compiled graph and resets it for the next frame. A graph with
+    /// passes that was not compiled is reset without running anything.
+    pub fn execute(&mut self, context: &mut FrameGraphContext) -> Result<(), FrameGraphError> {
+        let result = match &self.compiled_frame_graph {
+            Some(compiled_frame_graph) => compiled_frame_graph.execute(context),
+            None if self.pass_nodes.is_empty() => Ok(()),
+            None => Err(FrameGraphError::NotCompiled),
+        };
+
+        self.reset();
+
+        result
+    }
+
+    /// Checks that every read refers to a version some pass produced, or to the
+    /// initial contents of an imported resource, and that no earlier registered pass
+    /// has already overwritten the sub-resources it covers. Problems the pass
+    /// builders found while recording are reported first.
+    fn validate(&self) -> Result<(), CompileError> {
+        let mut diagnostics = self.builder_diagnostics.clone();
+        let mut producers: HashMap<GraphRawResourceHandle, usize> = HashMap::new();
+
+        for pass_node in self.pass_nodes.iter() {
+            for resource_handle in pass_node.writes.iter() {
+                if let Some(first_pass) = producers.get(resource_handle) {
+                    let resource_node = &self.resource_nodes[resource_handle.index.index];
+
+                    diagnostics.push(CompileDiagnostic::DuplicateWrite {
+                        first_pass: self.pass_nodes[*first_pass].name.clone(),
+                        second_pass: pass_node.name.clone(),
+                        resource: resource_node.name.clone(),
+                        version: resource_handle.version,
+                    });
+                } else {
+                    producers.insert(resource_handle.clone(), pass_node.index.index);
+                }
+            }
+        }
+
+        let tracker = SubresourceTracker::new(self, |_| true);
+
+        let mut latest_versions = self
+            .resource_nodes
+            .iter()
+            .map(|resource_node| vec![0; tracker.extent(resource_node.index).len()])
+            .collect::<Vec<_>>();
+
+        for pass_node in self.pass_nodes.iter() {
+            for resource_handle in pass_node.reads.iter() {
+                let resource_node = &self.resource_nodes[resource_handle.index.index];
+                let latest_version = resource_handle
+                    .subresource
+                    .cells(tracker.extent(resource_handle.index))
+                    .map(|cell| latest_versions[resource_node.index.index][cell])
+                    .max()
+                    .unwrap_or_default();
+
+                if resource_handle.version < latest_version {
+                    diagnostics.push(CompileDiagnostic::ReadStaleVersion {
+                        pass: pass_node.name.clone(),
+                        resource: resource_node.name.clone(),
+                        version: resource_handle.version,
+                        latest_version,
+                    });
+
+                    continue;
+                }
+
+                let (_, covered) = tracker.producers(resource_handle);
+
+                if !covered && !resource_node.is_persistent() {
+                    diagnostics.push(CompileDiagnostic::ReadUninitialized {
+                        pass: pass_node.name.clone(),
+                        resource: resource_node.name.clone(),
+                        version: resource_handle.version,
+                    });
+                }
+            }
+
+            for resource_handle in pass_node.writes.iter() {
+                let latest_versions = &mut latest_versions[resource_handle.index.index];
+
+                for cell in resource_handle
+                    .subresource
+                    .cells(tracker.extent(resource_handle.index))
+                {
+                    latest_versions[cell] = resource_handle.version.max(latest_versions[cell]);
+                }
+            }
         }
 
-        if let Some(compiled_frame_graph) = &mut self.compiled_frame_graph {
-            compiled_frame_graph.execute(context);
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(CompileError::Invalid { diagnostics })
         }
+    }
 
-        self.reset();
+    /// Culling by reachability. A pass survives if a surviving pass reads or writes
+    /// over the sub-resources it wrote, if it writes an imported, history or exported
+    /// resource, or if it is marked as having side effects. Passes that declare no
+    /// writes at all are kept, since the graph can't tell what they touch.
+    fn cull(&mut self) {
+        let tracker = SubresourceTracker::new(self, |_| true);
+
+        let mut alive = vec![false; self.pass_nodes.len()];
+        let mut unvisited = vec![];
+
+        for pass_node in self.pass_nodes.iter() {
+            let is_root = pass_node.has_side_effect
+                || pass_node.writes.is_empty()
+                || pass_node.writes.iter().any(|resource_handle| {
+                    let resource_node = &self.resource_nodes[resource_handle.index.index];
+                    resource_node.is_persistent() || resource_node.exported
+                });
+
+            if is_root {
+                alive[pass_node.index.index] = true;
+                unvisited.push(pass_node.index.index);
+            }
+        }
+
+        while let Some(index) = unvisited.pop() {
+            let pass_node = &self.pass_nodes[index];
+
+            let producers = pass_node
+                .reads
+                .iter()
+                .flat_map(|resource_handle| tracker.producers(resource_handle).0)
+                .chain(
+                    pass_node
+                        .writes
+                        .iter()
+                        .flat_map(|resource_handle| tracker.previous_producers(resource_handle)),
+                );
+
+            for producer in producers {
+                if !alive[producer] {
+                    alive[producer] = true;
+                    unvisited.push(producer);
+                }
+            }
+        }
+
+        for pass_node in self.pass_nodes.iter_mut() {
+            pass_node.culled = !alive[pass_node.index.index];
+        }
+    }
+
+    /// ORs the usages the surviving passes declare into the descriptors of the
+    /// transient resources they touch. Imported resources can't change, so any usage
+    /// they lack is reported instead.
+    fn infer_usages(&mut self) -> Result<(), CompileError> {
+        let mut diagnostics = vec![];
+
+        for pass_node in self.pass_nodes.iter().filter(|pass_node| !pass_node.culled) {
+            for (index, usage) in pass_node.usages.iter() {
+                let resource_node = &mut self.resource_nodes[index.index];
+
+                match &mut resource_node.resource {
+                    VirtualResource::Setuped(desc) | VirtualResource::History { desc, .. } => {
+                        desc.add_usage(*usage)
+                    }
+                    VirtualResource::Imported(resource) => {
+                        let missing = usage.difference(&resource.get_desc().usage());
+
+                        if !missing.is_empty() {
+                            diagnostics.push(CompileDiagnostic::MissingImportedUsage {
+                                pass: pass_node.name.clone(),
+                                resource: resource_node.name.clone(),
+                                usage: missing,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // The two halves of a history pair swap roles every frame, so both need the
+        // usages of either.
+        let mut history_descs: HashMap<String, AnyTransientResourceDescriptor> = HashMap::new();
+
+        for resource_node in self.resource_nodes.iter() {
+            if let VirtualResource::History { key, desc, .. } = &resource_node.resource {
+                history_descs
+                    .entry(key.clone())
+                    .or_insert_with(|| desc.clone())
+                    .add_usage(desc.usage());
+            }
+        }
+
+        for resource_node in self.resource_nodes.iter_mut() {
+            if let VirtualResource::History { key, desc, .. } = &mut resource_node.resource {
+                desc.add_usage(history_descs[key].usage());
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(CompileError::Invalid { diagnostics })
+        }
+    }
+
+    /// Orders the surviving passes so every pass runs after the producers of each
+    /// sub-resource it reads, after their previous writers for each it writes, and
+    /// after every reader of the contents it overwrites. Ties keep registration order.
+    fn sort_passes(&mut self) -> Result<(), CompileError> {
+        let tracker = SubresourceTracker::new(self, |index| !self.pass_nodes[index].culled);
+
+        let mut dependents: Vec<Vec<usize>> = vec![vec![]; self.pass_nodes.len()];
+        let mut dependencies_of: Vec<Vec<usize>> = vec![vec![]; self.pass_nodes.len()];
+        let mut in_degrees = vec![0usize; self.pass_nodes.len()];
+
+        for pass_node in self.pass_nodes.iter().filter(|pass_node| !pass_node.culled) {
+            let index = pass_node.index.index;
+            let mut dependencies = vec![];
+
+            for resource_handle in pass_node.reads.iter() {
+                dependencies.extend(tracker.producers(resource_handle).0);
+            }
+
+            for resource_handle in pass_node.writes.iter() {
+                dependencies.extend(tracker.hazards(resource_handle));
+            }
+
+            dependencies.sort_unstable();
+            dependencies.dedup();
+
+            for dependency in dependencies {
+                if dependency == index {
+                    continue;
+                }
+
+                dependents[dependency].push(index);
+                dependencies_of[index].push(dependency);
+                in_degrees[index] += 1;
+            }
+        }
+
+        let mut ready = self
+            .pass_nodes
+            .iter()
+            .filter(|pass_node| !pass_node.culled && in_degrees[pass_node.index.index] == 0)
+            .map(|pass_node| Reverse(pass_node.index.index))
+            .collect::<BinaryHeap<_>>();
+
+        let mut execution_order = vec![];
+
+        while let Some(Reverse(index)) = ready.pop() {
+            execution_order.push(IndexHandle::new(index));
+
+            for &dependent in dependents[index].iter() {
+                in_degrees[dependent] -= 1;
+
+                if in_degrees[dependent] == 0 {
+                    ready.push(Reverse(dependent));
+                }
+            }
+        }
+
+        let pass_count = self
+            .pass_nodes
+            .iter()
+            .filter(|pass_node| !pass_node.culled)
+            .count();
+
+        if execution_order.len() != pass_count {
+            // Every pass left unsorted waits on another unsorted one, so walking those
+            // dependencies from any of them must come back around to a pass on a cycle.
+            let mut walk = vec![];
+            let mut index = self
+                .pass_nodes
+                .iter()
+                .position(|pass_node| !pass_node.culled && in_degrees[pass_node.index.index] > 0)
+                .unwrap();
+
+            let start = loop {
+                if let Some(start) = walk.iter().position(|&visited| visited == index) {
+                    break start;
+                }
+
+                walk.push(index);
+                index = *dependencies_of[index]
+                    .iter()
+                    .find(|&&dependency| in_degrees[dependency] > 0)
+                    .unwrap();
+            };
+
+            let mut cycle = walk.split_off(start);
+            cycle.sort_unstable();
+
+            let passes = cycle
+                .into_iter()
+                .map(|index| self.pass_nodes[index].name.clone())
+                .collect();
+
+            return Err(CompileError::Cycle { passes });
+        }
+
+        self.execution_order = execution_order;
+
+        Ok(())
     }
 
     fn compute_resource_lifetime(&mut self) {
-        for pass_node in self.pass_nodes.iter_mut() {
+        for pass_node_handle in self.execution_order.iter() {
+            let pass_node = &mut self.pass_nodes[pass_node_handle.index];
+
             for resource_handle in pass_node.reads.iter() {
                 let resource_node = &mut self.resource_nodes[resource_handle.index.index];
                 resource_node.update_lifetime(pass_node.index);
@@ -127,15 +597,15 @@ impl FrameGraph {
         }
     }
 
-    fn generate_compiled_frame_graph(&mut self) {
+    fn generate_compiled_frame_graph(&mut self, memory_report: TransientMemoryReport) {
         if self.pass_nodes.is_empty() {
             return;
         }
 
         let mut device_passes = vec![];
 
-        for index in 0..self.pass_nodes.len() {
-            let type_index = self.pass_nodes[index].index;
+        for index in 0..self.execution_order.len() {
+            let type_index = self.execution_order[index];
 
             let mut device_pass = DevicePass::default();
             device_pass.extra(self, type_index);
@@ -143,17 +613,59 @@ impl FrameGraph {
             device_passes.push(device_pass);
         }
 
-        self.compiled_frame_graph = Some(CompiledFrameGraph { device_passes });
+        self.compiled_frame_graph = Some(CompiledFrameGraph {
+            device_passes,
+            execution_order: self.execution_order.clone(),
+            dot_graph: DotGraph::new(self),
+            memory_report,
+            reused: false,
+        });
     }
 
-    pub fn compile(&mut self) {
+    /// Compiles the graph for `execute`. A graph that fails to compile is reset, so
+    /// the next frame starts over instead of inheriting the invalid passes.
+    pub fn compile(&mut self) -> Result<(), CompileError> {
+        let result = self.compile_passes();
+
+        if result.is_err() {
+            self.reset();
+        }
+
+        result
+    }
+
+    fn compile_passes(&mut self) -> Result<(), CompileError> {
         if self.pass_nodes.is_empty() {
-            return;
+            return Ok(());
         }
-        //todo cull
 
+        if self.retained {
+            let structure_hash = self.structure_hash();
+            self.structure_hash = Some(structure_hash);
+
+            if let Some(retained_frame_graph) = self
+                .retained_frame_graph
+                .take()
+                .filter(|retained| retained.structure_hash == structure_hash)
+                .filter(|_| self.builder_diagnostics.is_empty())
+            {
+                let mut compiled_frame_graph = retained_frame_graph.compiled_frame_graph;
+                compiled_frame_graph.refresh(self);
+                self.compiled_frame_graph = Some(compiled_frame_graph);
+
+                return Ok(());
+            }
+        }
+
+        self.validate()?;
+        self.cull();
+        self.infer_usages()?;
+        self.sort_passes()?;
         self.compute_resource_lifetime();
-        self.generate_compiled_frame_graph();
+        let memory_report = self.plan_aliasing();
+        self.generate_compiled_frame_graph(memory_report);
+
+        Ok(())
     }
 }
 
@@ -168,7 +680,7 @@ impl FrameGraph {
 
         self.resource_board
             .get(&key)
-            .map(|handle| self.resource_nodes[handle.index].get_handle())
+            .and_then(|handle| self.resource_nodes[handle.index].try_get_handle())
     }
 
     pub fn pass_node(&mut self, name: &str) -> &mut PassNode {
@@ -0,0 +1,226 @@
+use std::{
+    mem::{take, transmute},
+    panic::{AssertUnwindSafe, catch_unwind, resume_unwind},
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, SendError, Sender, channel},
+    },
+    thread::{self, JoinHandle},
+};
+
+use wgpu::CommandBuffer;
+
+use crate::{CompiledFrameGraph, FrameGraphContext, FrameGraphError, gfx_base::RenderDeviceEvent};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Threads that record passes in parallel, see
+/// [`FrameGraphContext::with_recording_pool`]. They are spawned once and live as long
+/// as the pool, so create it up front and hand it to the context every frame.
+pub struct RecordingThreadPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl RecordingThreadPool {
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let receiver = receiver.clone();
+
+                thread::Builder::new()
+                    .name(format!("frame-graph-recording-{index}"))
+                    .spawn(move || Self::work(&receiver))
+                    .expect("failed to spawn pass recording thread")
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    fn work(receiver: &Mutex<Receiver<Job>>) {
+        loop {
+            let job = receiver.lock().unwrap().recv();
+
+            match job {
+                Ok(job) => job(),
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Runs every task on the pool and returns their results in order, blocking
+    /// until all of them finished. A panicking task is resumed on the calling thread
+    /// once the others are done.
+    pub(crate) fn run<'scope, T: Send + 'scope>(
+        &self,
+        tasks: Vec<Box<dyn FnOnce() -> T + Send + 'scope>>,
+    ) -> Vec<T> {
+        let (result_sender, result_receiver) = channel();
+        let mut pending = PendingResults {
+            sender: Some(result_sender),
+            receiver: result_receiver,
+            results: (0..tasks.len()).map(|_| None).collect(),
+            outstanding: 0,
+        };
+
+        for (index, task) in tasks.into_iter().enumerate() {
+            let result_sender = pending.sender.clone().unwrap();
+
+            let job: Box<dyn FnOnce() + Send + 'scope> = Box::new(move || {
+                let result = catch_unwind(AssertUnwindSafe(task));
+                let _ = result_sender.send((index, result));
+            });
+
+            // SAFETY: the job only borrows data that lives for `'scope`. `pending`
+            // waits for every job it counts before this function returns or unwinds,
+            // and sending the result is the last thing a job does with its borrows.
+            let job = unsafe { transmute::<Box<dyn FnOnce() + Send + 'scope>, Job>(job) };
+
+            pending.outstanding += 1;
+
+            if let Err(SendError(job)) = self.sender.as_ref().unwrap().send(job) {
+                job();
+            }
+        }
+
+        pending.wait_all();
+
+        take(&mut pending.results)
+            .into_iter()
+            .map(|result| match result {
+                Some(Ok(value)) => value,
+                Some(Err(payload)) => resume_unwind(payload),
+                None => panic!("pass recording job dropped without finishing"),
+            })
+            .collect()
+    }
+}
+
+/// Results of the jobs [`RecordingThreadPool::run`] handed out. Dropping it waits
+/// for the jobs still running, so a panic in `run` can't unwind past borrows the
+/// workers hold.
+struct PendingResults<T> {
+    sender: Option<Sender<(usize, thread::Result<T>)>>,
+    receiver: Receiver<(usize, thread::Result<T>)>,
+    results: Vec<Option<thread::Result<T>>>,
+    outstanding: usize,
+}
+
+impl<T> PendingResults<T> {
+    fn wait_all(&mut self) {
+        // Without our own sender, receiving fails only once every job was either
+        // finished or dropped without running, so none is left borrowing.
+        self.sender = None;
+
+        while self.outstanding > 0 {
+            match self.receiver.recv() {
+                Ok((index, result)) => {
+                    self.results[index] = Some(result);
+                    self.outstanding -= 1;
+                }
+                Err(_) => self.outstanding = 0,
+            }
+        }
+    }
+}
+
+impl<T> Drop for PendingResults<T> {
+    fn drop(&mut self) {
+        self.wait_all();
+    }
+}
+
+impl Drop for RecordingThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl CompiledFrameGraph {
+    /// Requests and releases resources serially in execution order, keeping every
+    /// resource in the table, then records contiguous chunks of passes on the
+    /// threads of `pool`. Command buffers are collected in execution order, so
+    /// submitting them keeps the dependencies intact.
+    pub(crate) fn execute_parallel(
+        &self,
+        context: &mut FrameGraphContext,
+        pool: &RecordingThreadPool,
+    ) -> Result<(), FrameGraphError> {
+        for device_pass in self.device_passes.iter() {
+            context
+                .render_device
+                .record(RenderDeviceEvent::ExecutePass {
+                    name: device_pass.name.clone(),
+                });
+            device_pass.request_resources(context);
+            device_pass.release_resources_retained(context);
+        }
+
+        let chunk_size = self.device_passes.len().div_ceil(pool.threads()).max(1);
+
+        let render_device = &context.render_device;
+        let resource_table = &context.resource_table;
+        let pipeline_container = &context.pipeline_container;
+        let bind_group_cache = context.transient_resource_cache.bind_group_cache();
+        let texture_view_cache = context.transient_resource_cache.texture_view_cache();
+
+        let tasks = self
+            .device_passes
+            .chunks(chunk_size)
+            .map(|device_passes| {
+                Box::new(move || {
+                    device_passes
+                        .iter()
+                        .map(|device_pass| {
+                            let mut command_buffers: Vec<CommandBuffer> = vec![];
+                            device_pass
+                                .record(
+                                    &mut command_buffers,
+                                    render_device,
+                                    resource_table,
+                                    pipeline_container,
+                                    bind_group_cache,
+                                    texture_view_cache,
+                                )
+                                .map(|_| command_buffers)
+                        })
+                        .collect::<Vec<_>>()
+                }) as Box<dyn FnOnce() -> _ + Send>
+            })
+            .collect();
+
+        let results = pool.run(tasks).into_iter().flatten().collect::<Vec<_>>();
+
+        context.resource_table.clear();
+
+        let mut errors = vec![];
+
+        for result in results {
+            match result {
+                Ok(command_buffers) => context.command_buffers.extend(command_buffers),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FrameGraphError::Execute { errors })
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::mem::take;
+
+use crate::{
+    ComputePass, Ref, ResourceRead, TransientBindGroup, TransientBuffer, gfx_base::CachedPipelineId,
+};
+
+use super::{ComputePassExt, PassBuilder};
+
+pub struct ComputePassBuilder<'a, 'b> {
+    compute_pass: ComputePass,
+    pass_builder: &'b mut PassBuilder<'a>,
+}
+
+impl<'a, 'b> ComputePassBuilder<'a, 'b> {
+    pub fn new(pass_builder: &'b mut PassBuilder<'a>, name: &str) -> Self {
+        let mut compute_pass = ComputePass::default();
+        compute_pass.set_pass_name(name);
+
+        Self {
+            compute_pass,
+            pass_builder,
+        }
+    }
+
+    pub fn set_compute_pipeline(&mut self, id: CachedPipelineId) -> &mut Self {
+        self.compute_pass.set_compute_pipeline(id);
+        self
+    }
+
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &TransientBindGroup,
+        offsets: &[u32],
+    ) -> &mut Self {
+        self.compute_pass.set_bind_group(index, bind_group, offsets);
+
+        self
+    }
+
+    pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) -> &mut Self {
+        self.compute_pass.dispatch_workgroups(x, y, z);
+        self
+    }
+
+    pub fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> &mut Self {
+        self.compute_pass
+            .dispatch_workgroups_indirect(indirect_buffer_ref, indirect_offset);
+        self
+    }
+
+    pub fn create_compute_pass_builder(&mut self) -> &mut Self {
+        self.finish();
+
+        self
+    }
+
+    fn finish(&mut self) {
+        let compute_pass = take(&mut self.compute_pass);
+        self.pass_builder.push(compute_pass);
+    }
+}
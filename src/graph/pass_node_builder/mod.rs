@@ -1,13 +1,16 @@
+mod compute_pass_builder;
 mod parameter;
 mod pass_builder;
 mod render_pass_builder;
 
-pub use parameter::RenderPassExt;
+pub use compute_pass_builder::*;
+pub use parameter::{ComputePassExt, RenderPassExt};
 pub use pass_builder::*;
 pub use render_pass_builder::*;
 
 use crate::{
-    GraphRawResourceHandle, Handle, Pass, Ref, ResourceRead, ResourceWrite, TransientResource,
+    CompileDiagnostic, GraphRawResourceHandle, Handle, Pass, Ref, ResourceRead, ResourceWrite,
+    TransientResource,
 };
 
 use super::{FrameGraph, ResourceMaterial};
@@ -18,6 +21,7 @@ pub struct PassNodeBuilder<'a> {
     writes: Vec<GraphRawResourceHandle>,
     reads: Vec<GraphRawResourceHandle>,
     pass: Option<Pass>,
+    has_side_effect: bool,
 }
 
 impl Drop for PassNodeBuilder<'_> {
@@ -26,6 +30,12 @@ impl Drop for PassNodeBuilder<'_> {
         pass_node.writes = self.writes.clone();
         pass_node.reads = self.reads.clone();
         pass_node.pass = self.pass.take();
+
+        if let Some(pass) = &pass_node.pass {
+            pass.collect_usages(&mut pass_node.usages);
+        }
+
+        pass_node.has_side_effect = self.has_side_effect;
     }
 }
 
@@ -65,6 +75,7 @@ impl<'a> PassNodeBuilderExt for PassNodeBuilder<'a> {
         let new_raw = GraphRawResourceHandle {
             index,
             version: resource_node.version(),
+            subresource: resource_handle.raw.subresource,
         };
 
         self.writes.push(new_raw.clone());
@@ -104,10 +115,35 @@ impl<'a> PassNodeBuilderExt for PassNodeBuilder<'a> {
 
 impl<'a> PassNodeBuilder<'a> {
     pub fn set_pass(&mut self, mut pass: Pass) {
-        pass.label = Some(self.name.clone().into());
+        pass.label = Some(self.name.clone());
         self.pass = Some(pass);
     }
 
+    /// Keeps the pass alive during culling even if nothing reads its writes.
+    pub fn make_side_effect(&mut self) {
+        self.has_side_effect = true;
+    }
+
+    /// Drops a write this pass declared and reads the version it would have replaced
+    /// instead, for passes that turn out to only look at the contents.
+    pub(crate) fn demote_write<ResourceType: TransientResource>(
+        &mut self,
+        resource_ref: Ref<ResourceType, ResourceWrite>,
+    ) -> Ref<ResourceType, ResourceRead> {
+        let raw = resource_ref.raw;
+        self.writes.retain(|write| *write != raw);
+
+        let previous = Handle::new(raw.index, raw.version.saturating_sub(1), resource_ref.desc)
+            .with_subresource(raw.subresource);
+
+        self.read(previous)
+    }
+
+    /// Reports a problem with how the pass was recorded when the graph is compiled.
+    pub(crate) fn add_diagnostic(&mut self, diagnostic: CompileDiagnostic) {
+        self.graph.builder_diagnostics.push(diagnostic);
+    }
+
     pub fn new(name: &str, graph: &'a mut FrameGraph) -> Self {
         Self {
             graph,
@@ -115,6 +151,7 @@ impl<'a> PassNodeBuilder<'a> {
             writes: vec![],
             reads: vec![],
             pass: None,
+            has_side_effect: false,
         }
     }
 }
@@ -0,0 +1,24 @@
+use crate::{
+    ComputePassCommand, ComputePassContext, FrameGraphError, Ref, ResourceRead, ResourceUsages,
+    TransientBuffer,
+};
+use wgpu::BufferUsages;
+
+pub struct DispatchWorkgroupsIndirectParameter {
+    pub indirect_buffer_ref: Ref<TransientBuffer, ResourceRead>,
+    pub indirect_offset: u64,
+}
+
+impl ComputePassCommand for DispatchWorkgroupsIndirectParameter {
+    fn execute(
+        &self,
+        compute_pass_context: &mut ComputePassContext,
+    ) -> Result<(), FrameGraphError> {
+        compute_pass_context
+            .dispatch_workgroups_indirect(&self.indirect_buffer_ref, self.indirect_offset)
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.indirect_buffer_ref, BufferUsages::INDIRECT);
+    }
+}
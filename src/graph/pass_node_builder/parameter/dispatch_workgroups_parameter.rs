@@ -0,0 +1,16 @@
+use crate::{ComputePassCommand, ComputePassContext, FrameGraphError};
+
+pub struct DispatchWorkgroupsParameter {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl ComputePassCommand for DispatchWorkgroupsParameter {
+    fn execute(
+        &self,
+        compute_pass_context: &mut ComputePassContext,
+    ) -> Result<(), FrameGraphError> {
+        compute_pass_context.dispatch_workgroups(self.x, self.y, self.z)
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{
+    FrameGraphError, Ref, RenderPassCommand, RenderPassContext, ResourceRead, ResourceUsages,
+    TransientBuffer,
+};
+use wgpu::BufferUsages;
+
+pub struct DrawIndirectParameter {
+    pub indirect_buffer_ref: Ref<TransientBuffer, ResourceRead>,
+    pub indirect_offset: u64,
+}
+
+impl RenderPassCommand for DrawIndirectParameter {
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
+        render_pass_context.draw_indirect(&self.indirect_buffer_ref, self.indirect_offset)
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.indirect_buffer_ref, BufferUsages::INDIRECT);
+    }
+}
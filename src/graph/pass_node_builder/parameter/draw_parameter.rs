@@ -0,0 +1,14 @@
+use std::ops::Range;
+
+use crate::{FrameGraphError, RenderPassCommand, RenderPassContext};
+
+pub struct DrawParameter {
+    pub vertices: Range<u32>,
+    pub instances: Range<u32>,
+}
+
+impl RenderPassCommand for DrawParameter {
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
+        render_pass_context.draw(self.vertices.clone(), self.instances.clone())
+    }
+}
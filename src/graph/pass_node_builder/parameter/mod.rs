@@ -1,12 +1,29 @@
+mod dispatch_workgroups_indirect_parameter;
+mod dispatch_workgroups_parameter;
+mod draw_indexed_indirect_parameter;
+mod draw_indexed_parameter;
+mod draw_indirect_parameter;
+mod draw_parameter;
 mod set_bind_group_parameter;
+mod set_compute_pipeline_parameter;
 mod set_index_buffer_parameter;
 mod set_vertex_buffer_parameter;
 mod set_render_pipeline_parameter;
 
+use std::ops::Range;
+
 use crate::{
-    Ref, RenderPass, RenderPassCommand, ResourceRead, TransientBindGroup, TransientBuffer, gfx_base::CachedPipelineId,
+    ComputePass, ComputePassCommand, Ref, RenderPass, RenderPassCommand, ResourceRead,
+    TransientBindGroup, TransientBuffer, gfx_base::CachedPipelineId,
 };
+use dispatch_workgroups_indirect_parameter::*;
+use dispatch_workgroups_parameter::*;
+use draw_indexed_indirect_parameter::*;
+use draw_indexed_parameter::*;
+use draw_indirect_parameter::*;
+use draw_parameter::*;
 use set_bind_group_parameter::*;
+use set_compute_pipeline_parameter::*;
 use set_index_buffer_parameter::*;
 use set_vertex_buffer_parameter::*;
 use set_render_pipeline_parameter::*;
@@ -55,6 +72,43 @@ pub trait RenderPassExt {
             size,
         });
     }
+
+    fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.push(DrawParameter {
+            vertices,
+            instances,
+        });
+    }
+
+    fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>) {
+        self.push(DrawIndexedParameter {
+            indices,
+            base_vertex,
+            instances,
+        });
+    }
+
+    fn draw_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) {
+        self.push(DrawIndirectParameter {
+            indirect_buffer_ref: indirect_buffer_ref.clone(),
+            indirect_offset,
+        });
+    }
+
+    fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) {
+        self.push(DrawIndexedIndirectParameter {
+            indirect_buffer_ref: indirect_buffer_ref.clone(),
+            indirect_offset,
+        });
+    }
 }
 
 impl RenderPassExt for RenderPass {
@@ -62,3 +116,40 @@ impl RenderPassExt for RenderPass {
         self.commands.push(Box::new(value));
     }
 }
+
+pub trait ComputePassExt {
+    fn push<T: ComputePassCommand>(&mut self, value: T);
+
+    fn set_compute_pipeline(&mut self, id: CachedPipelineId) {
+        self.push(SetComputePipelineParameter { id });
+    }
+
+    fn set_bind_group(&mut self, index: u32, bind_group: &TransientBindGroup, offsets: &[u32]) {
+        self.push(SetBindGroupParameter {
+            index,
+            bind_group: bind_group.clone(),
+            offsets: offsets.to_vec(),
+        });
+    }
+
+    fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        self.push(DispatchWorkgroupsParameter { x, y, z });
+    }
+
+    fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) {
+        self.push(DispatchWorkgroupsIndirectParameter {
+            indirect_buffer_ref: indirect_buffer_ref.clone(),
+            indirect_offset,
+        });
+    }
+}
+
+impl ComputePassExt for ComputePass {
+    fn push<T: ComputePassCommand>(&mut self, value: T) {
+        self.commands.push(Box::new(value));
+    }
+}
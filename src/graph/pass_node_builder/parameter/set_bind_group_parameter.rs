@@ -1,4 +1,7 @@
-use crate::{RenderPassCommand, RenderPassContext, TransientBindGroup};
+use crate::{
+    ComputePassCommand, ComputePassContext, FrameGraphError, RenderPassCommand, RenderPassContext,
+    ResourceUsages, TransientBindGroup,
+};
 
 pub struct SetBindGroupParameter {
     pub index: u32,
@@ -7,7 +10,24 @@ pub struct SetBindGroupParameter {
 }
 
 impl RenderPassCommand for SetBindGroupParameter {
-    fn execute(&self, render_pass_context: &mut RenderPassContext) {
-        render_pass_context.set_bind_group(self.index, &self.bind_group, &self.offsets);
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
+        render_pass_context.set_bind_group(self.index, &self.bind_group, &self.offsets)
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        self.bind_group.collect_usages(usages);
+    }
+}
+
+impl ComputePassCommand for SetBindGroupParameter {
+    fn execute(
+        &self,
+        compute_pass_context: &mut ComputePassContext,
+    ) -> Result<(), FrameGraphError> {
+        compute_pass_context.set_bind_group(self.index, &self.bind_group, &self.offsets)
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        self.bind_group.collect_usages(usages);
     }
 }
@@ -0,0 +1,14 @@
+use crate::{ComputePassCommand, ComputePassContext, FrameGraphError, gfx_base::CachedPipelineId};
+
+pub struct SetComputePipelineParameter {
+    pub id: CachedPipelineId,
+}
+
+impl ComputePassCommand for SetComputePipelineParameter {
+    fn execute(
+        &self,
+        compute_pass_context: &mut ComputePassContext,
+    ) -> Result<(), FrameGraphError> {
+        compute_pass_context.set_compute_pipeline(self.id)
+    }
+}
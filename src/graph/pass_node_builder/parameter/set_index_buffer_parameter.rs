@@ -1,5 +1,8 @@
-use crate::{Ref, RenderPassCommand, RenderPassContext, ResourceRead, TransientBuffer};
-use wgpu::IndexFormat;
+use crate::{
+    FrameGraphError, Ref, RenderPassCommand, RenderPassContext, ResourceRead, ResourceUsages,
+    TransientBuffer,
+};
+use wgpu::{BufferUsages, IndexFormat};
 
 pub struct SetIndexBufferParameter {
     pub buffer_ref: Ref<TransientBuffer, ResourceRead>,
@@ -9,12 +12,16 @@ pub struct SetIndexBufferParameter {
 }
 
 impl RenderPassCommand for SetIndexBufferParameter {
-    fn execute(&self, render_pass_context: &mut RenderPassContext) {
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
         render_pass_context.set_index_buffer(
             &self.buffer_ref,
             self.index_format,
             self.offset,
             self.size,
-        );
+        )
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.buffer_ref, BufferUsages::INDEX);
     }
 }
@@ -1,11 +1,11 @@
-use crate::{RenderPassCommand, RenderPassContext, gfx_base::CachedPipelineId};
+use crate::{FrameGraphError, RenderPassCommand, RenderPassContext, gfx_base::CachedPipelineId};
 
 pub struct SetRenderPipelineParameter {
     pub id: CachedPipelineId,
 }
 
 impl RenderPassCommand for SetRenderPipelineParameter {
-    fn execute(&self, render_pass_context: &mut RenderPassContext) {
-        render_pass_context.set_render_pipeline(self.id);
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
+        render_pass_context.set_render_pipeline(self.id)
     }
 }
@@ -1,4 +1,8 @@
-use crate::{Ref, RenderPassCommand, RenderPassContext, ResourceRead, TransientBuffer};
+use crate::{
+    FrameGraphError, Ref, RenderPassCommand, RenderPassContext, ResourceRead, ResourceUsages,
+    TransientBuffer,
+};
+use wgpu::BufferUsages;
 
 pub struct SetVertexBufferParameter {
     pub slot: u32,
@@ -8,7 +12,11 @@ pub struct SetVertexBufferParameter {
 }
 
 impl RenderPassCommand for SetVertexBufferParameter {
-    fn execute(&self, render_pass_context: &mut RenderPassContext) {
-        render_pass_context.set_vertex_buffer(self.slot, &self.buffer_ref, self.offset, self.size);
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError> {
+        render_pass_context.set_vertex_buffer(self.slot, &self.buffer_ref, self.offset, self.size)
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.buffer_ref, BufferUsages::VERTEX);
     }
 }
@@ -5,7 +5,7 @@ use crate::{
     ResourceWrite, TransientResource,
 };
 
-use super::{PassNodeBuilder, RenderPassBuilder};
+use super::{ComputePassBuilder, PassNodeBuilder, RenderPassBuilder};
 
 pub struct PassBuilder<'a> {
     pass_node_builder: PassNodeBuilder<'a>,
@@ -61,7 +61,19 @@ impl<'a> PassBuilder<'a> {
         RenderPassBuilder::new(self, name)
     }
 
+    pub fn create_compute_pass_builder<'b>(&'b mut self, name: &str) -> ComputePassBuilder<'a, 'b> {
+        ComputePassBuilder::new(self, name)
+    }
+
+    pub fn make_side_effect(&mut self) {
+        self.pass_node_builder.make_side_effect();
+    }
+
     pub fn push<T: PassCommand>(&mut self, command: T) {
         self.pass.push(command);
     }
+
+    pub(crate) fn pass_node_builder(&mut self) -> &mut PassNodeBuilder<'a> {
+        &mut self.pass_node_builder
+    }
 }
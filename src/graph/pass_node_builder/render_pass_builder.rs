@@ -1,8 +1,9 @@
-use std::mem::take;
+use std::{mem::take, ops::Range};
 
 use crate::{
-    Ref, RenderPass, ResourceRead, TransientBindGroup, TransientBuffer,
-    TransientRenderPassColorAttachment, gfx_base::CachedPipelineId,
+    CompileDiagnostic, PassNodeBuilderExt, Ref, RenderPass, ResourceRead, TransientBindGroup,
+    TransientBuffer, TransientRenderPassColorAttachment, TransientRenderPassDepthStencilAttachment,
+    TransientTextureView, TransientTextureViewDescriptor, gfx_base::CachedPipelineId,
 };
 
 use super::{PassBuilder, RenderPassExt};
@@ -32,6 +33,52 @@ impl<'a, 'b> RenderPassBuilder<'a, 'b> {
         self
     }
 
+    /// Sets the depth-stencil attachment. A read view is promoted to a write when
+    /// either aspect stores its result, so later passes see the new version. A write
+    /// view that stores neither but loads one is demoted to a read of the contents
+    /// it tests against, since a discarded attachment produces nothing. A view
+    /// without a depth or stencil format fails compilation.
+    pub fn set_depth_stencil_attachment(
+        &mut self,
+        mut depth_stencil_attachment: TransientRenderPassDepthStencilAttachment,
+    ) -> &mut Self {
+        if let Some(format) = depth_stencil_attachment.view.format()
+            && !format.is_depth_stencil_format()
+        {
+            let pass_node_builder = self.pass_builder.pass_node_builder();
+            let pass = pass_node_builder.name.clone();
+
+            pass_node_builder
+                .add_diagnostic(CompileDiagnostic::InvalidDepthStencilFormat { pass, format });
+        }
+
+        let is_store = depth_stencil_attachment.is_store();
+        let is_load = depth_stencil_attachment.is_load();
+
+        depth_stencil_attachment.view = match depth_stencil_attachment.view {
+            TransientTextureView::Read(desc) if is_store => {
+                TransientTextureView::Write(TransientTextureViewDescriptor {
+                    texture: self.pass_builder.write(desc.texture.handle()),
+                    desc: desc.desc,
+                })
+            }
+            TransientTextureView::Write(desc) if !is_store && is_load => {
+                TransientTextureView::Read(TransientTextureViewDescriptor {
+                    texture: self
+                        .pass_builder
+                        .pass_node_builder()
+                        .demote_write(desc.texture),
+                    desc: desc.desc,
+                })
+            }
+            view => view,
+        };
+
+        self.render_pass
+            .set_depth_stencil_attachment(Some(depth_stencil_attachment));
+        self
+    }
+
     pub fn set_render_pipeline(&mut self, id: CachedPipelineId) -> &mut Self {
         self.render_pass.set_render_pipeline(id);
         self
@@ -73,6 +120,42 @@ impl<'a, 'b> RenderPassBuilder<'a, 'b> {
         self
     }
 
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) -> &mut Self {
+        self.render_pass.draw(vertices, instances);
+        self
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        indices: Range<u32>,
+        base_vertex: i32,
+        instances: Range<u32>,
+    ) -> &mut Self {
+        self.render_pass
+            .draw_indexed(indices, base_vertex, instances);
+        self
+    }
+
+    pub fn draw_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> &mut Self {
+        self.render_pass
+            .draw_indirect(indirect_buffer_ref, indirect_offset);
+        self
+    }
+
+    pub fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> &mut Self {
+        self.render_pass
+            .draw_indexed_indirect(indirect_buffer_ref, indirect_offset);
+        self
+    }
+
     pub fn create_render_pass_builder(&mut self) -> &mut Self {
         self.finish();
 
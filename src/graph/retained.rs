@@ -0,0 +1,88 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use crate::{CompiledFrameGraph, FrameGraph, VirtualResource};
+
+/// A compiled graph kept across frames, keyed by the structure it was built from.
+pub(crate) struct RetainedFrameGraph {
+    pub(crate) structure_hash: u64,
+    pub(crate) compiled_frame_graph: CompiledFrameGraph,
+}
+
+impl CompiledFrameGraph {
+    /// Moves the per-frame payloads of `frame_graph` into the passes compiled last
+    /// frame: the recorded commands and the imported resources. Everything derived
+    /// from the structure is kept as is, including the descriptors of transient and
+    /// history resources, which compilation extended with inferred usages and
+    /// replaced with alias slots. The nodes of `frame_graph` only hold what was
+    /// declared.
+    pub(crate) fn refresh(&mut self, frame_graph: &mut FrameGraph) {
+        for (device_pass, handle) in self
+            .device_passes
+            .iter_mut()
+            .zip(self.execution_order.iter())
+        {
+            device_pass.pass = frame_graph.get_pass_node_mut(handle).pass.take();
+
+            for request in device_pass.resource_request_array.iter_mut() {
+                let resource = &frame_graph.get_resource_node(&request.index).resource;
+
+                if let VirtualResource::Imported(_) = resource {
+                    request.resource = resource.clone();
+                }
+            }
+        }
+
+        self.reused = true;
+    }
+}
+
+impl FrameGraph {
+    /// Hash of everything compilation depends on: pass names, side effects, reads,
+    /// writes and usages, and the name and descriptor of every resource.
+    pub(crate) fn structure_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.pass_nodes.len().hash(&mut hasher);
+
+        for pass_node in self.pass_nodes.iter() {
+            pass_node.name.hash(&mut hasher);
+            pass_node.has_side_effect.hash(&mut hasher);
+            pass_node.reads.hash(&mut hasher);
+            pass_node.writes.hash(&mut hasher);
+            pass_node.usages.hash(&mut hasher);
+        }
+
+        self.resource_nodes.len().hash(&mut hasher);
+
+        for resource_node in self.resource_nodes.iter() {
+            resource_node.name.hash(&mut hasher);
+            resource_node.exported.hash(&mut hasher);
+
+            match &resource_node.resource {
+                VirtualResource::Setuped(desc) => {
+                    0u8.hash(&mut hasher);
+                    desc.hash(&mut hasher);
+                }
+                VirtualResource::Imported(resource) => {
+                    1u8.hash(&mut hasher);
+                    resource.get_desc().hash(&mut hasher);
+                }
+                VirtualResource::History {
+                    key,
+                    desc,
+                    previous,
+                } => {
+                    2u8.hash(&mut hasher);
+                    key.hash(&mut hasher);
+                    desc.hash(&mut hasher);
+                    previous.hash(&mut hasher);
+                }
+            }
+        }
+
+        hasher.finish()
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{FrameGraph, GraphRawResourceHandle, IndexHandle, ResourceNode, SubresourceExtent};
+
+struct Access {
+    handle: GraphRawResourceHandle,
+    pass: usize,
+}
+
+/// Every read and write of the graph, grouped by resource, to resolve accesses
+/// scoped to parts of a resource. Versions count writes to any part of it.
+pub(crate) struct SubresourceTracker {
+    extents: Vec<SubresourceExtent>,
+    writes: Vec<Vec<Access>>,
+    reads: Vec<Vec<Access>>,
+}
+
+impl SubresourceTracker {
+    /// Collects the accesses of the passes `include` accepts, in registration order.
+    pub(crate) fn new(frame_graph: &FrameGraph, include: impl Fn(usize) -> bool) -> Self {
+        let extents = frame_graph
+            .resource_nodes
+            .iter()
+            .map(|resource_node| resource_node.resource.get_any_desc().subresource_extent())
+            .collect();
+
+        let mut writes: Vec<Vec<Access>> =
+            frame_graph.resource_nodes.iter().map(|_| vec![]).collect();
+        let mut reads: Vec<Vec<Access>> =
+            frame_graph.resource_nodes.iter().map(|_| vec![]).collect();
+
+        for pass_node in frame_graph.pass_nodes.iter() {
+            let pass = pass_node.index.index;
+
+            if !include(pass) {
+                continue;
+            }
+
+            for handle in pass_node.writes.iter() {
+                writes[handle.index.index].push(Access {
+                    handle: handle.clone(),
+                    pass,
+                });
+            }
+
+            for handle in pass_node.reads.iter() {
+                reads[handle.index.index].push(Access {
+                    handle: handle.clone(),
+                    pass,
+                });
+            }
+        }
+
+        for accesses in writes.iter_mut() {
+            accesses.sort_by_key(|access| access.handle.version);
+        }
+
+        Self {
+            extents,
+            writes,
+            reads,
+        }
+    }
+
+    pub(crate) fn extent(&self, index: IndexHandle<ResourceNode>) -> SubresourceExtent {
+        self.extents[index.index]
+    }
+
+    /// The passes whose writes `handle` sees: for every sub-resource it covers, the
+    /// last one written at or before its version. The flag is false if some
+    /// sub-resource was never written by then.
+    pub(crate) fn producers(&self, handle: &GraphRawResourceHandle) -> (Vec<usize>, bool) {
+        let extent = self.extent(handle.index);
+        let writes = &self.writes[handle.index.index];
+
+        let mut producers = vec![];
+        let mut covered = true;
+
+        for cell in handle.subresource.cells(extent) {
+            let producer = writes
+                .iter()
+                .rev()
+                .filter(|write| write.handle.version <= handle.version)
+                .find(|write| write.handle.subresource.contains(cell, extent));
+
+            match producer {
+                Some(write) => producers.push(write.pass),
+                None => covered = false,
+            }
+        }
+
+        producers.sort_unstable();
+        producers.dedup();
+
+        (producers, covered)
+    }
+
+    /// The passes a write through `handle` has to wait for: the previous writers of
+    /// the sub-resources it covers, and every pass reading them at an older version.
+    pub(crate) fn hazards(&self, handle: &GraphRawResourceHandle) -> Vec<usize> {
+        let mut hazards = self.previous_producers(handle);
+
+        let extent = self.extent(handle.index);
+
+        hazards.extend(
+            self.reads[handle.index.index]
+                .iter()
+                .filter(|read| {
+                    read.handle.version < handle.version
+                        && read
+                            .handle
+                            .subresource
+                            .overlaps(&handle.subresource, extent)
+                })
+                .map(|read| read.pass),
+        );
+
+        hazards.sort_unstable();
+        hazards.dedup();
+
+        hazards
+    }
+
+    /// The passes whose contents a write through `handle` replaces. Writes aren't
+    /// assumed to cover all they declare, so these stay alive as long as it does.
+    pub(crate) fn previous_producers(&self, handle: &GraphRawResourceHandle) -> Vec<usize> {
+        if handle.version == 0 {
+            return vec![];
+        }
+
+        let previous = GraphRawResourceHandle {
+            version: handle.version - 1,
+            ..handle.clone()
+        };
+
+        self.producers(&previous).0
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use crate::{
+    FrameGraph, Handle, PassNodeBuilder, PassNodeBuilderExt, TransientTexture,
+    gfx_base::GpuSurfaceTexture,
+};
+
+impl FrameGraph {
+    /// Imports the texture of the current swapchain image so passes can write it like
+    /// any other texture. Pass the final handle to [`FrameGraph::present`], and keep
+    /// `surface_texture` around to present once the graph has executed.
+    pub fn import_surface_texture(
+        &mut self,
+        name: &str,
+        surface_texture: &GpuSurfaceTexture,
+    ) -> Handle<TransientTexture> {
+        let texture = TransientTexture::from_gpu_texture(surface_texture.texture());
+
+        self.import(name, Arc::new(texture))
+    }
+
+    /// Adds a side-effect pass reading `handle`, so the passes writing the texture to
+    /// be presented are never culled. It records no commands; presenting is up to
+    /// [`FrameGraphContext::submit_and_present`](crate::FrameGraphContext::submit_and_present).
+    pub fn present(&mut self, handle: Handle<TransientTexture>) {
+        let name = format!("present {}", self.get_resource_node(&handle.raw.index).name);
+
+        let mut builder = PassNodeBuilder::new(&name, self);
+        builder.read(handle);
+        builder.make_side_effect();
+    }
+}
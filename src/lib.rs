@@ -11,6 +11,10 @@ mod graph;
 mod resource_board;
 mod device_pass;
 mod bind_group;
+mod bindless;
+mod error;
+mod resource_usage;
+mod subresource;
 
 pub use transient_resource::*;
 pub use pass::*;
@@ -22,4 +26,8 @@ pub use resource_table::*;
 pub use graph::*;
 pub use resource_board::*;
 pub use device_pass::*;
-pub use bind_group::*;
\ No newline at end of file
+pub use bind_group::*;
+pub use bindless::*;
+pub use error::*;
+pub use resource_usage::*;
+pub use subresource::*;
\ No newline at end of file
@@ -0,0 +1,66 @@
+use crate::{
+    FrameGraphError, PassContext, Ref, ResourceRead, TransientBindGroup, TransientBuffer,
+    gfx_base::{CachedPipelineId, GpuComputePass},
+};
+
+pub struct ComputePassContext<'a, 'b> {
+    compute_pass: GpuComputePass,
+    pass_context: &'b mut PassContext<'a>,
+}
+
+impl<'a, 'b> ComputePassContext<'a, 'b> {
+    pub fn new(compute_pass: GpuComputePass, pass_context: &'b mut PassContext<'a>) -> Self {
+        ComputePassContext {
+            compute_pass,
+            pass_context,
+        }
+    }
+
+    pub fn set_compute_pipeline(&mut self, id: CachedPipelineId) -> Result<(), FrameGraphError> {
+        let pipeline = self.pass_context.try_get_compute_pipeline(id)?;
+        self.compute_pass
+            .get_compute_pass_mut()
+            .set_pipeline(pipeline.wgpu());
+
+        Ok(())
+    }
+
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &TransientBindGroup,
+        offsets: &[u32],
+    ) -> Result<(), FrameGraphError> {
+        let bind_group = bind_group.create_bind_group(self.pass_context)?;
+
+        self.compute_pass.get_compute_pass_mut().set_bind_group(
+            index,
+            bind_group.get_wgpu_bind_group(),
+            offsets,
+        );
+
+        Ok(())
+    }
+
+    pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) -> Result<(), FrameGraphError> {
+        self.compute_pass
+            .get_compute_pass_mut()
+            .dispatch_workgroups(x, y, z);
+
+        Ok(())
+    }
+
+    pub fn dispatch_workgroups_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> Result<(), FrameGraphError> {
+        let buffer = self.pass_context.try_get_resource(indirect_buffer_ref)?;
+
+        self.compute_pass
+            .get_compute_pass_mut()
+            .dispatch_workgroups_indirect(buffer.resource.get_wgpu_buffer(), indirect_offset);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+mod context;
+
+pub use context::*;
+
+use crate::{
+    FrameGraphError, ResourceUsages,
+    gfx_base::{ComputePassDescriptor, GpuComputePass},
+    pass::{PassCommand, PassContext},
+};
+
+pub trait ComputePassCommand: Sync + Send + 'static {
+    fn execute(&self, compute_pass_context: &mut ComputePassContext)
+    -> Result<(), FrameGraphError>;
+
+    fn collect_usages(&self, _usages: &mut ResourceUsages) {}
+}
+
+#[derive(Default)]
+pub struct ComputePass {
+    desc: ComputePassDescriptor,
+    pub(crate) commands: Vec<Box<dyn ComputePassCommand>>,
+}
+
+impl ComputePass {
+    pub fn set_pass_name(&mut self, name: &str) {
+        self.desc.label = Some(name.to_string());
+    }
+}
+
+impl PassCommand for ComputePass {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let compute_pass =
+            GpuComputePass::begin_compute_pass(&mut context.command_encoder, &self.desc);
+        let mut compute_pass_context = ComputePassContext::new(compute_pass, context);
+
+        for command in self.commands.iter() {
+            command.execute(&mut compute_pass_context)?;
+        }
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        for command in self.commands.iter() {
+            command.collect_usages(usages);
+        }
+    }
+}
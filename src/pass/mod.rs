@@ -1,14 +1,19 @@
+mod compute_pass;
 mod render_pass;
+mod transfer;
 
+pub use compute_pass::*;
 pub use render_pass::*;
+pub use transfer::*;
 
 use wgpu::{CommandBuffer, CommandEncoder};
 
 use crate::{
-    Ref, ResourceTable, ResourceView, TransientResource,
+    BindGroupCache, FrameGraphError, Ref, ResourceTable, ResourceUsages, ResourceView,
+    TextureViewCache, TransientResource,
     gfx_base::{
-        CachedPipelineId, CommandEncoderDescriptor, GpuRenderPipeline, PipelineContainer,
-        RenderDevice,
+        CachedPipelineId, CommandEncoderDescriptor, GpuComputePipeline, GpuRenderPipeline,
+        PipelineContainer, RenderDevice,
     },
 };
 
@@ -17,6 +22,8 @@ pub struct PassContext<'a> {
     command_encoder: CommandEncoder,
     resource_table: &'a ResourceTable,
     pipeline_container: &'a PipelineContainer,
+    bind_group_cache: &'a BindGroupCache,
+    texture_view_cache: &'a TextureViewCache,
 }
 
 impl PassContext<'_> {
@@ -24,30 +31,71 @@ impl PassContext<'_> {
         self.resource_table
     }
 
+    pub fn bind_group_cache(&self) -> &BindGroupCache {
+        self.bind_group_cache
+    }
+
+    pub fn texture_view_cache(&self) -> &TextureViewCache {
+        self.texture_view_cache
+    }
+
     pub fn render_device(&self) -> &RenderDevice {
         self.render_device
     }
 
-    pub fn get_render_pipeline(&self, id: CachedPipelineId) -> &GpuRenderPipeline {
+    pub fn try_get_render_pipeline(
+        &self,
+        id: CachedPipelineId,
+    ) -> Result<&GpuRenderPipeline, FrameGraphError> {
         self.pipeline_container
             .get_render_pipeline(id)
+            .ok_or(FrameGraphError::MissingRenderPipeline { id })
+    }
+
+    pub fn get_render_pipeline(&self, id: CachedPipelineId) -> &GpuRenderPipeline {
+        self.try_get_render_pipeline(id)
             .expect("render pipeline mut have")
     }
 
+    pub fn try_get_compute_pipeline(
+        &self,
+        id: CachedPipelineId,
+    ) -> Result<&GpuComputePipeline, FrameGraphError> {
+        self.pipeline_container
+            .get_compute_pipeline(id)
+            .ok_or(FrameGraphError::MissingComputePipeline { id })
+    }
+
+    pub fn get_compute_pipeline(&self, id: CachedPipelineId) -> &GpuComputePipeline {
+        self.try_get_compute_pipeline(id)
+            .expect("compute pipeline mut have")
+    }
+
     pub fn finish(self) -> CommandBuffer {
         self.command_encoder.finish()
     }
 
+    pub fn try_get_resource<ResourceType: TransientResource, ViewType: ResourceView>(
+        &self,
+        resource_ref: &Ref<ResourceType, ViewType>,
+    ) -> Result<&ResourceType, FrameGraphError> {
+        self.resource_table.try_get_resource(resource_ref)
+    }
+
     pub fn get_resource<ResourceType: TransientResource, ViewType: ResourceView>(
         &self,
         resource_ref: &Ref<ResourceType, ViewType>,
     ) -> &ResourceType {
-        self.resource_table.get_resource(&resource_ref)
+        self.resource_table.get_resource(resource_ref)
     }
 }
 
 pub trait PassCommand: 'static + Send + Sync {
-    fn execute(&self, context: &mut PassContext);
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError>;
+
+    /// Declares how the command uses its resources, so the compiler can add the
+    /// matching usage flags to transient descriptors.
+    fn collect_usages(&self, _usages: &mut ResourceUsages) {}
 }
 
 #[derive(Default)]
@@ -61,13 +109,21 @@ impl Pass {
         self.commands.push(Box::new(value));
     }
 
+    pub fn collect_usages(&self, usages: &mut ResourceUsages) {
+        for command in self.commands.iter() {
+            command.collect_usages(usages);
+        }
+    }
+
     pub fn render(
         &self,
         command_buffers: &mut Vec<CommandBuffer>,
         render_device: &RenderDevice,
         resource_table: &ResourceTable,
         pipeline_container: &PipelineContainer,
-    ) {
+        bind_group_cache: &BindGroupCache,
+        texture_view_cache: &TextureViewCache,
+    ) -> Result<(), FrameGraphError> {
         let command_encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
             label: self.label.clone(),
         });
@@ -77,11 +133,15 @@ impl Pass {
             command_encoder,
             resource_table,
             pipeline_container,
+            bind_group_cache,
+            texture_view_cache,
         };
 
         for command in self.commands.iter() {
-            command.execute(&mut pass_context);
+            command.execute(&mut pass_context)?;
         }
         command_buffers.push(pass_context.finish());
+
+        Ok(())
     }
 }
@@ -1,5 +1,7 @@
+use std::ops::Range;
+
 use crate::{
-    PassContext, Ref, ResourceRead, TransientBindGroup, TransientBuffer,
+    FrameGraphError, PassContext, Ref, ResourceRead, TransientBindGroup, TransientBuffer,
     gfx_base::{CachedPipelineId, GpuRenderPass},
 };
 
@@ -16,21 +18,30 @@ impl<'a, 'b> RenderPassContext<'a, 'b> {
         }
     }
 
-    pub fn set_render_pipeline(&mut self, id: CachedPipelineId) {
-        let pipeline = self.pass_context.get_render_pipeline(id);
+    pub fn set_render_pipeline(&mut self, id: CachedPipelineId) -> Result<(), FrameGraphError> {
+        let pipeline = self.pass_context.try_get_render_pipeline(id)?;
         self.render_pass
             .get_render_pass_mut()
             .set_pipeline(pipeline.wgpu());
+
+        Ok(())
     }
 
-    pub fn set_bind_group(&mut self, index: u32, bind_group: &TransientBindGroup, offsets: &[u32]) {
-        let bind_group = bind_group.create_bind_group(self.pass_context);
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &TransientBindGroup,
+        offsets: &[u32],
+    ) -> Result<(), FrameGraphError> {
+        let bind_group = bind_group.create_bind_group(self.pass_context)?;
 
         self.render_pass.get_render_pass_mut().set_bind_group(
             index,
             bind_group.get_wgpu_bind_group(),
             offsets,
         );
+
+        Ok(())
     }
 
     pub fn set_vertex_buffer(
@@ -39,8 +50,8 @@ impl<'a, 'b> RenderPassContext<'a, 'b> {
         buffer_ref: &Ref<TransientBuffer, ResourceRead>,
         offset: u64,
         size: u64,
-    ) {
-        let buffer = self.pass_context.resource_table.get_resource(buffer_ref);
+    ) -> Result<(), FrameGraphError> {
+        let buffer = self.pass_context.try_get_resource(buffer_ref)?;
         self.render_pass.get_render_pass_mut().set_vertex_buffer(
             slot,
             buffer
@@ -48,6 +59,8 @@ impl<'a, 'b> RenderPassContext<'a, 'b> {
                 .get_wgpu_buffer()
                 .slice(offset..(offset + size)),
         );
+
+        Ok(())
     }
 
     pub fn set_index_buffer(
@@ -56,8 +69,8 @@ impl<'a, 'b> RenderPassContext<'a, 'b> {
         index_format: wgpu::IndexFormat,
         offset: u64,
         size: u64,
-    ) {
-        let buffer = self.pass_context.resource_table.get_resource(buffer_ref);
+    ) -> Result<(), FrameGraphError> {
+        let buffer = self.pass_context.try_get_resource(buffer_ref)?;
 
         self.render_pass.get_render_pass_mut().set_index_buffer(
             buffer
@@ -66,5 +79,60 @@ impl<'a, 'b> RenderPassContext<'a, 'b> {
                 .slice(offset..(offset + size)),
             index_format,
         );
+
+        Ok(())
+    }
+
+    pub fn draw(
+        &mut self,
+        vertices: Range<u32>,
+        instances: Range<u32>,
+    ) -> Result<(), FrameGraphError> {
+        self.render_pass
+            .get_render_pass_mut()
+            .draw(vertices, instances);
+
+        Ok(())
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        indices: Range<u32>,
+        base_vertex: i32,
+        instances: Range<u32>,
+    ) -> Result<(), FrameGraphError> {
+        self.render_pass
+            .get_render_pass_mut()
+            .draw_indexed(indices, base_vertex, instances);
+
+        Ok(())
+    }
+
+    pub fn draw_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> Result<(), FrameGraphError> {
+        let buffer = self.pass_context.try_get_resource(indirect_buffer_ref)?;
+
+        self.render_pass
+            .get_render_pass_mut()
+            .draw_indirect(buffer.resource.get_wgpu_buffer(), indirect_offset);
+
+        Ok(())
+    }
+
+    pub fn draw_indexed_indirect(
+        &mut self,
+        indirect_buffer_ref: &Ref<TransientBuffer, ResourceRead>,
+        indirect_offset: u64,
+    ) -> Result<(), FrameGraphError> {
+        let buffer = self.pass_context.try_get_resource(indirect_buffer_ref)?;
+
+        self.render_pass
+            .get_render_pass_mut()
+            .draw_indexed_indirect(buffer.resource.get_wgpu_buffer(), indirect_offset);
+
+        Ok(())
     }
 }
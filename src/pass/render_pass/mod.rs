@@ -2,10 +2,10 @@ mod context;
 
 pub use context::*;
 
-use wgpu::{Color, Operations};
+use wgpu::{Color, LoadOp, Operations, StoreOp, TextureUsages};
 
 use crate::{
-    TransientTextureView,
+    FrameGraphError, ResourceUsages, TransientTextureView,
     gfx_base::{
         GpuRenderPass, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
         RenderPassDescriptor,
@@ -24,16 +24,17 @@ impl TransientRenderPassColorAttachment {
     pub fn create_render_pass_color_attachment(
         &self,
         context: &PassContext,
-    ) -> RenderPassColorAttachment {
-        RenderPassColorAttachment {
-            view: self.view.create_gpu_texture_view(context),
-            depth_slice: self.depth_slice.clone(),
+    ) -> Result<RenderPassColorAttachment, FrameGraphError> {
+        Ok(RenderPassColorAttachment {
+            view: self.view.create_gpu_texture_view(context)?,
+            depth_slice: self.depth_slice,
             resolve_target: self
                 .resolve_target
                 .as_ref()
-                .map(|resolve_target| resolve_target.create_gpu_texture_view(context)),
-            ops: self.ops.clone(),
-        }
+                .map(|resolve_target| resolve_target.create_gpu_texture_view(context))
+                .transpose()?,
+            ops: self.ops,
+        })
     }
 }
 
@@ -44,15 +45,33 @@ pub struct TransientRenderPassDepthStencilAttachment {
 }
 
 impl TransientRenderPassDepthStencilAttachment {
+    /// Whether either aspect keeps its contents after the pass.
+    pub fn is_store(&self) -> bool {
+        self.depth_ops
+            .is_some_and(|ops| ops.store == StoreOp::Store)
+            || self
+                .stencil_ops
+                .is_some_and(|ops| ops.store == StoreOp::Store)
+    }
+
+    /// Whether either aspect starts from the contents the texture had before the pass.
+    pub fn is_load(&self) -> bool {
+        self.depth_ops
+            .is_some_and(|ops| matches!(ops.load, LoadOp::Load))
+            || self
+                .stencil_ops
+                .is_some_and(|ops| matches!(ops.load, LoadOp::Load))
+    }
+
     pub fn create_render_pass_depth_stencil_attachment(
         &self,
         context: &PassContext,
-    ) -> RenderPassDepthStencilAttachment {
-        RenderPassDepthStencilAttachment {
-            view: self.view.create_gpu_texture_view(context),
-            depth_ops: self.depth_ops.clone(),
-            stencil_ops: self.stencil_ops.clone(),
-        }
+    ) -> Result<RenderPassDepthStencilAttachment, FrameGraphError> {
+        Ok(RenderPassDepthStencilAttachment {
+            view: self.view.create_gpu_texture_view(context)?,
+            depth_ops: self.depth_ops,
+            stencil_ops: self.stencil_ops,
+        })
     }
 }
 
@@ -64,29 +83,39 @@ pub struct TransientRenderPassDescriptor {
 }
 
 impl TransientRenderPassDescriptor {
-    pub fn create_render_pass_descriptor(&self, context: &PassContext) -> RenderPassDescriptor {
-        RenderPassDescriptor {
+    pub fn create_render_pass_descriptor(
+        &self,
+        context: &PassContext,
+    ) -> Result<RenderPassDescriptor, FrameGraphError> {
+        Ok(RenderPassDescriptor {
             label: self.label.clone(),
             color_attachments: self
                 .color_attachments
                 .iter()
                 .map(|color_attachment| {
-                    color_attachment.as_ref().map(|color_attachment| {
-                        color_attachment.create_render_pass_color_attachment(context)
-                    })
+                    color_attachment
+                        .as_ref()
+                        .map(|color_attachment| {
+                            color_attachment.create_render_pass_color_attachment(context)
+                        })
+                        .transpose()
                 })
-                .collect(),
-            depth_stencil_attachment: self.depth_stencil_attachment.as_ref().map(
-                |depth_stencil_attachment| {
+                .collect::<Result<_, _>>()?,
+            depth_stencil_attachment: self
+                .depth_stencil_attachment
+                .as_ref()
+                .map(|depth_stencil_attachment| {
                     depth_stencil_attachment.create_render_pass_depth_stencil_attachment(context)
-                },
-            ),
-        }
+                })
+                .transpose()?,
+        })
     }
 }
 
 pub trait RenderPassCommand: Sync + Send + 'static {
-    fn execute(&self, render_pass_context: &mut RenderPassContext);
+    fn execute(&self, render_pass_context: &mut RenderPassContext) -> Result<(), FrameGraphError>;
+
+    fn collect_usages(&self, _usages: &mut ResourceUsages) {}
 }
 
 #[derive(Default)]
@@ -106,16 +135,46 @@ impl RenderPass {
     ) {
         self.desc.color_attachments.push(color_attachment);
     }
+
+    pub fn set_depth_stencil_attachment(
+        &mut self,
+        depth_stencil_attachment: Option<TransientRenderPassDepthStencilAttachment>,
+    ) {
+        self.desc.depth_stencil_attachment = depth_stencil_attachment;
+    }
 }
 
 impl PassCommand for RenderPass {
-    fn execute(&self, context: &mut PassContext) {
-        let desc = self.desc.create_render_pass_descriptor(context);
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let desc = self.desc.create_render_pass_descriptor(context)?;
         let render_pass = GpuRenderPass::begin_render_pass(&mut context.command_encoder, &desc);
         let mut render_pass_context = RenderPassContext::new(render_pass, context);
 
         for command in self.commands.iter() {
-            command.execute(&mut render_pass_context);
+            command.execute(&mut render_pass_context)?;
+        }
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        for color_attachment in self.desc.color_attachments.iter().flatten() {
+            usages.add_texture_view(&color_attachment.view, TextureUsages::RENDER_ATTACHMENT);
+
+            if let Some(resolve_target) = &color_attachment.resolve_target {
+                usages.add_texture_view(resolve_target, TextureUsages::RENDER_ATTACHMENT);
+            }
+        }
+
+        if let Some(depth_stencil_attachment) = &self.desc.depth_stencil_attachment {
+            usages.add_texture_view(
+                &depth_stencil_attachment.view,
+                TextureUsages::RENDER_ATTACHMENT,
+            );
+        }
+
+        for command in self.commands.iter() {
+            command.collect_usages(usages);
         }
     }
 }
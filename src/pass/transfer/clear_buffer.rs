@@ -0,0 +1,29 @@
+use wgpu::{BufferAddress, BufferUsages};
+
+use crate::{
+    FrameGraphError, PassCommand, PassContext, Ref, ResourceUsages, ResourceWrite, TransientBuffer,
+};
+
+pub struct ClearBuffer {
+    pub buffer: Ref<TransientBuffer, ResourceWrite>,
+    pub offset: BufferAddress,
+    pub size: Option<BufferAddress>,
+}
+
+impl PassCommand for ClearBuffer {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let buffer = context.resource_table.try_get_resource(&self.buffer)?;
+
+        context.command_encoder.clear_buffer(
+            buffer.resource.get_wgpu_buffer(),
+            self.offset,
+            self.size,
+        );
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.buffer, BufferUsages::COPY_DST);
+    }
+}
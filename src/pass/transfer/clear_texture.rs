@@ -0,0 +1,26 @@
+use wgpu::{ImageSubresourceRange, TextureUsages};
+
+use crate::{
+    FrameGraphError, PassCommand, PassContext, Ref, ResourceUsages, ResourceWrite, TransientTexture,
+};
+
+pub struct ClearTexture {
+    pub texture: Ref<TransientTexture, ResourceWrite>,
+    pub subresource_range: ImageSubresourceRange,
+}
+
+impl PassCommand for ClearTexture {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let texture = context.resource_table.try_get_resource(&self.texture)?;
+
+        context
+            .command_encoder
+            .clear_texture(texture.resource.get_wgpu_texture(), &self.subresource_range);
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_texture(&self.texture, TextureUsages::COPY_DST);
+    }
+}
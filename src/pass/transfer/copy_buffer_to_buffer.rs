@@ -0,0 +1,36 @@
+use wgpu::{BufferAddress, BufferUsages};
+
+use crate::{
+    FrameGraphError, PassCommand, PassContext, Ref, ResourceRead, ResourceUsages, ResourceWrite,
+    TransientBuffer,
+};
+
+pub struct CopyBufferToBuffer {
+    pub source: Ref<TransientBuffer, ResourceRead>,
+    pub source_offset: BufferAddress,
+    pub destination: Ref<TransientBuffer, ResourceWrite>,
+    pub destination_offset: BufferAddress,
+    pub size: Option<BufferAddress>,
+}
+
+impl PassCommand for CopyBufferToBuffer {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let source = context.resource_table.try_get_resource(&self.source)?;
+        let destination = context.resource_table.try_get_resource(&self.destination)?;
+
+        context.command_encoder.copy_buffer_to_buffer(
+            source.resource.get_wgpu_buffer(),
+            self.source_offset,
+            destination.resource.get_wgpu_buffer(),
+            self.destination_offset,
+            self.size,
+        );
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.source, BufferUsages::COPY_SRC);
+        usages.add_buffer(&self.destination, BufferUsages::COPY_DST);
+    }
+}
@@ -0,0 +1,35 @@
+use wgpu::{BufferUsages, Extent3d, TextureUsages};
+
+use crate::{
+    FrameGraphError, PassCommand, PassContext, ResourceRead, ResourceUsages, ResourceWrite,
+};
+
+use super::{TransientTexelCopyBufferInfo, TransientTexelCopyTextureInfo};
+
+pub struct CopyBufferToTexture {
+    pub source: TransientTexelCopyBufferInfo<ResourceRead>,
+    pub destination: TransientTexelCopyTextureInfo<ResourceWrite>,
+    pub copy_size: Extent3d,
+}
+
+impl PassCommand for CopyBufferToTexture {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let source = self
+            .source
+            .get_texel_copy_buffer_info(context.resource_table)?;
+        let destination = self
+            .destination
+            .get_texel_copy_texture_info(context.resource_table)?;
+
+        context
+            .command_encoder
+            .copy_buffer_to_texture(source, destination, self.copy_size);
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_buffer(&self.source.buffer, BufferUsages::COPY_SRC);
+        usages.add_texture(&self.destination.texture, TextureUsages::COPY_DST);
+    }
+}
@@ -0,0 +1,35 @@
+use wgpu::{BufferUsages, Extent3d, TextureUsages};
+
+use crate::{
+    FrameGraphError, PassCommand, PassContext, ResourceRead, ResourceUsages, ResourceWrite,
+};
+
+use super::{TransientTexelCopyBufferInfo, TransientTexelCopyTextureInfo};
+
+pub struct CopyTextureToBuffer {
+    pub source: TransientTexelCopyTextureInfo<ResourceRead>,
+    pub destination: TransientTexelCopyBufferInfo<ResourceWrite>,
+    pub copy_size: Extent3d,
+}
+
+impl PassCommand for CopyTextureToBuffer {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let source = self
+            .source
+            .get_texel_copy_texture_info(context.resource_table)?;
+        let destination = self
+            .destination
+            .get_texel_copy_buffer_info(context.resource_table)?;
+
+        context
+            .command_encoder
+            .copy_texture_to_buffer(source, destination, self.copy_size);
+
+        Ok(())
+    }
+
+    fn collect_usages(&self, usages: &mut ResourceUsages) {
+        usages.add_texture(&self.source.texture, TextureUsages::COPY_SRC);
+        usages.add_buffer(&self.destination.buffer, BufferUsages::COPY_DST);
+    }
+}
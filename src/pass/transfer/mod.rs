@@ -0,0 +1,61 @@
+mod clear_buffer;
+mod clear_texture;
+mod copy_buffer_to_buffer;
+mod copy_buffer_to_texture;
+mod copy_texture_to_buffer;
+mod copy_texture_to_texture;
+
+pub use clear_buffer::*;
+pub use clear_texture::*;
+pub use copy_buffer_to_buffer::*;
+pub use copy_buffer_to_texture::*;
+pub use copy_texture_to_buffer::*;
+pub use copy_texture_to_texture::*;
+
+use wgpu::{
+    Origin3d, TexelCopyBufferInfo, TexelCopyBufferLayout, TexelCopyTextureInfo, TextureAspect,
+};
+
+use crate::{FrameGraphError, Ref, ResourceTable, ResourceView, TransientBuffer, TransientTexture};
+
+pub struct TransientTexelCopyBufferInfo<ViewType> {
+    pub buffer: Ref<TransientBuffer, ViewType>,
+    pub layout: TexelCopyBufferLayout,
+}
+
+impl<ViewType: ResourceView> TransientTexelCopyBufferInfo<ViewType> {
+    pub fn get_texel_copy_buffer_info<'a>(
+        &self,
+        resource_table: &'a ResourceTable,
+    ) -> Result<TexelCopyBufferInfo<'a>, FrameGraphError> {
+        let buffer = resource_table.try_get_resource(&self.buffer)?;
+
+        Ok(TexelCopyBufferInfo {
+            buffer: buffer.resource.get_wgpu_buffer(),
+            layout: self.layout,
+        })
+    }
+}
+
+pub struct TransientTexelCopyTextureInfo<ViewType> {
+    pub texture: Ref<TransientTexture, ViewType>,
+    pub mip_level: u32,
+    pub origin: Origin3d,
+    pub aspect: TextureAspect,
+}
+
+impl<ViewType: ResourceView> TransientTexelCopyTextureInfo<ViewType> {
+    pub fn get_texel_copy_texture_info<'a>(
+        &self,
+        resource_table: &'a ResourceTable,
+    ) -> Result<TexelCopyTextureInfo<'a>, FrameGraphError> {
+        let texture = resource_table.try_get_resource(&self.texture)?;
+
+        Ok(TexelCopyTextureInfo {
+            texture: texture.resource.get_wgpu_texture(),
+            mip_level: self.mip_level,
+            origin: self.origin,
+            aspect: self.aspect,
+        })
+    }
+}
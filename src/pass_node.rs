@@ -1,4 +1,4 @@
-use crate::Pass;
+use crate::{Pass, ResourceUsages};
 
 use super::{GraphRawResourceHandle, IndexHandle, ResourceNode};
 
@@ -10,6 +10,9 @@ pub struct PassNode {
     pub resource_request_array: Vec<IndexHandle<ResourceNode>>,
     pub resource_release_array: Vec<IndexHandle<ResourceNode>>,
     pub pass: Option<Pass>,
+    pub usages: ResourceUsages,
+    pub has_side_effect: bool,
+    pub culled: bool,
 }
 
 impl PassNode {
@@ -22,6 +25,9 @@ impl PassNode {
             resource_request_array: Default::default(),
             resource_release_array: Default::default(),
             pass: Default::default(),
+            usages: Default::default(),
+            has_side_effect: false,
+            culled: false,
         }
     }
 }
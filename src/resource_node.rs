@@ -1,8 +1,12 @@
-use core::{fmt::Debug, marker::PhantomData};
+use core::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
 use crate::{
-    AnyTransientResourceDescriptor, ArcTransientResource, IndexHandle, PassNode, TransientResource,
-    TransientResourceDescriptor,
+    AnyTransientResourceDescriptor, ArcTransientResource, IndexHandle, PassNode, SubresourceRange,
+    TransientResource, TransientResourceDescriptor,
 };
 
 pub struct Ref<ResourceType: TransientResource, VieType> {
@@ -31,6 +35,16 @@ impl<ResourceType: TransientResource, VieType> Ref<ResourceType, VieType> {
             _marker: PhantomData,
         }
     }
+
+    /// Handle to the version this reference points at, so a written resource can be
+    /// read by later passes. It keeps the sub-resource range of this reference.
+    pub fn handle(&self) -> Handle<ResourceType> {
+        Handle {
+            raw: self.raw.clone(),
+            desc: self.desc.clone(),
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<ResourceType: TransientResource, VieType> Clone for Ref<ResourceType, VieType> {
@@ -51,10 +65,18 @@ impl<ResourceType: TransientResource, VieType> PartialEq for Ref<ResourceType, V
 
 impl<ResourceType: TransientResource, VieType> Eq for Ref<ResourceType, VieType> {}
 
+impl<ResourceType: TransientResource, VieType> Hash for Ref<ResourceType, VieType> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state);
+    }
+}
+
 pub trait ResourceView {}
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ResourceRead;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ResourceWrite;
 
 impl ResourceView for ResourceRead {}
@@ -84,17 +106,29 @@ impl<ResourceType: TransientResource> Handle<ResourceType> {
         desc: <ResourceType as TransientResource>::Descriptor,
     ) -> Self {
         Self {
-            raw: GraphRawResourceHandle { index, version },
+            raw: GraphRawResourceHandle {
+                index,
+                version,
+                subresource: SubresourceRange::FULL,
+            },
             desc,
             _marker: PhantomData,
         }
     }
+
+    /// Scopes reads and writes through this handle to `subresource`, so passes
+    /// using disjoint mips or layers of one texture don't depend on each other.
+    pub fn with_subresource(mut self, subresource: SubresourceRange) -> Self {
+        self.raw.subresource = subresource;
+        self
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct GraphRawResourceHandle {
     pub index: IndexHandle<ResourceNode>,
     pub version: u32,
+    pub subresource: SubresourceRange,
 }
 
 pub struct ResourceNode {
@@ -104,6 +138,7 @@ pub struct ResourceNode {
     pub last_user_pass: Option<IndexHandle<PassNode>>,
     version: u32,
     pub resource: VirtualResource,
+    pub exported: bool,
 }
 
 pub struct ResourceRequese {
@@ -113,23 +148,45 @@ pub struct ResourceRequese {
 
 pub struct ResourceRelease {
     pub index: IndexHandle<ResourceNode>,
+    pub exported: bool,
 }
 
 #[derive(Clone)]
 pub enum VirtualResource {
     Setuped(AnyTransientResourceDescriptor),
     Imported(ArcTransientResource),
+    /// One half of a history pair: this frame's resource, or last frame's if
+    /// `previous` is set.
+    History {
+        key: String,
+        desc: AnyTransientResourceDescriptor,
+        previous: bool,
+    },
 }
 
 impl VirtualResource {
-    pub fn get_desc<ResourceType: TransientResource>(&self) -> ResourceType::Descriptor {
-        let desc = match self {
+    pub fn get_any_desc(&self) -> AnyTransientResourceDescriptor {
+        match self {
             VirtualResource::Imported(resource) => resource.get_desc(),
             VirtualResource::Setuped(desc) => desc.clone(),
-        };
+            VirtualResource::History { desc, .. } => desc.clone(),
+        }
+    }
+
+    pub fn try_get_desc<ResourceType: TransientResource>(
+        &self,
+    ) -> Option<ResourceType::Descriptor> {
+        let desc = self.get_any_desc();
+
+        <ResourceType::Descriptor as TransientResourceDescriptor>::try_borrow_resource_descriptor(
+            &desc,
+        )
+        .cloned()
+    }
 
-        <ResourceType::Descriptor as TransientResourceDescriptor>::borrow_resource_descriptor(&desc)
-            .clone()
+    pub fn get_desc<ResourceType: TransientResource>(&self) -> ResourceType::Descriptor {
+        self.try_get_desc::<ResourceType>()
+            .expect("transient resource descriptor type mismatch")
     }
 }
 
@@ -142,6 +199,7 @@ impl ResourceNode {
             first_use_pass: None,
             last_user_pass: None,
             resource,
+            exported: false,
         }
     }
 }
@@ -159,12 +217,35 @@ impl ResourceNode {
         Handle::new(self.index, self.version, desc)
     }
 
+    pub fn try_get_handle<ResourceType: TransientResource>(&self) -> Option<Handle<ResourceType>> {
+        self.resource
+            .try_get_desc::<ResourceType>()
+            .map(|desc| Handle::new(self.index, self.version, desc))
+    }
+
     pub fn get_desc<ResourceType: TransientResource>(&self) -> ResourceType::Descriptor {
         self.resource.get_desc::<ResourceType>()
     }
 
     pub fn release(&self) -> ResourceRelease {
-        ResourceRelease { index: self.index }
+        ResourceRelease {
+            index: self.index,
+            exported: self.exported,
+        }
+    }
+
+    pub fn is_imported(&self) -> bool {
+        matches!(self.resource, VirtualResource::Imported(_))
+    }
+
+    pub fn is_history(&self) -> bool {
+        matches!(self.resource, VirtualResource::History { .. })
+    }
+
+    /// Whether the resource outlives the frame, so its contents at version 0 are
+    /// meaningful and its writes are observable after the graph runs.
+    pub fn is_persistent(&self) -> bool {
+        self.is_imported() || self.is_history()
     }
 
     pub fn version(&self) -> u32 {
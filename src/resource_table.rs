@@ -1,28 +1,55 @@
-use std::collections::HashMap;
+use std::{any::type_name, collections::HashMap, sync::Arc};
 
-use crate::gfx_base::RenderDevice;
+use crate::gfx_base::{RenderDevice, RenderDeviceEvent};
 use crate::{
-    AnyTransientResource, ArcTransientResource, IndexHandle, Ref, ResourceNode, ResourceRelease,
-    ResourceRequese, ResourceView, TransientResource, TransientResourceCache,
-    TransientResourceCreator, VirtualResource,
+    AnyTransientResource, ArcTransientResource, FrameGraphError, IndexHandle,
+    IntoArcTransientResource, Ref, ResourceNode, ResourceRelease, ResourceRequese, ResourceView,
+    TransientResource, TransientResourceCache, TransientResourceCreator, VirtualResource,
 };
 
 #[derive(Default)]
 pub struct ResourceTable {
     resources: HashMap<IndexHandle<ResourceNode>, AnyTransientResource>,
+    exported_resources: HashMap<IndexHandle<ResourceNode>, ArcTransientResource>,
 }
 
 impl ResourceTable {
+    pub fn try_get_resource<ResourceType: TransientResource, ViewType: ResourceView>(
+        &self,
+        resource_ref: &Ref<ResourceType, ViewType>,
+    ) -> Result<&ResourceType, FrameGraphError> {
+        let index = resource_ref.raw.index;
+        let resource = self
+            .resources
+            .get(&index)
+            .ok_or(FrameGraphError::MissingResource { index })?;
+
+        TransientResource::try_borrow_resource(resource).ok_or(
+            FrameGraphError::ResourceTypeMismatch {
+                index,
+                expected: type_name::<ResourceType>(),
+            },
+        )
+    }
+
     pub fn get_resource<ResourceType: TransientResource, ViewType: ResourceView>(
         &self,
         resource_ref: &Ref<ResourceType, ViewType>,
     ) -> &ResourceType {
-        self.resources
-            .get(&resource_ref.raw.index)
-            .map(|res| TransientResource::borrow_resource(res))
+        self.try_get_resource(resource_ref)
             .expect("must have resource")
     }
 
+    /// Takes a resource that was exported from the graph and released by its last
+    /// pass.
+    pub fn take_exported_resource<ResourceType: IntoArcTransientResource>(
+        &mut self,
+        index: IndexHandle<ResourceNode>,
+    ) -> Option<Arc<ResourceType>> {
+        let resource = self.exported_resources.remove(&index)?;
+        ResourceType::try_from_arc_transient_resource(resource)
+    }
+
     pub fn request_resource(
         &mut self,
         request: &ResourceRequese,
@@ -31,17 +58,21 @@ impl ResourceTable {
     ) {
         let index = request.index;
         let resource = match &request.resource {
-            VirtualResource::Imported(resource) => match &resource {
-                ArcTransientResource::Texture(resource) => {
-                    AnyTransientResource::ImportedTexture(resource.clone())
-                }
-                ArcTransientResource::Buffer(resource) => {
-                    AnyTransientResource::ImportedBuffer(resource.clone())
+            VirtualResource::Imported(resource) => resource.clone().into(),
+            VirtualResource::History {
+                key,
+                desc,
+                previous,
+            } => transient_resource_cache
+                .get_history_resource(key, desc, *previous, device)
+                .into(),
+            VirtualResource::Setuped(desc) => match transient_resource_cache.get_resource(desc) {
+                Some(resource) => {
+                    device.record(RenderDeviceEvent::ReuseResource { index: index.index });
+                    resource
                 }
+                None => device.create_resource(&transient_resource_cache.allocation_desc(desc)),
             },
-            VirtualResource::Setuped(desc) => transient_resource_cache
-                .get_resource(desc)
-                .unwrap_or_else(|| device.create_resource(desc)),
         };
 
         self.resources.insert(index, resource);
@@ -50,24 +81,63 @@ impl ResourceTable {
     pub fn release_resource(
         &mut self,
         release: &ResourceRelease,
+        device: &RenderDevice,
         transient_resource_cache: &mut TransientResourceCache,
     ) {
         if let Some(resource) = self.resources.remove(&release.index) {
-            match resource {
-                AnyTransientResource::OwnedBuffer(buffer) => {
-                    transient_resource_cache.insert_resource(
-                        buffer.desc.clone().into(),
-                        AnyTransientResource::OwnedBuffer(buffer),
-                    );
-                }
-                AnyTransientResource::OwnedTexture(texture) => {
-                    transient_resource_cache.insert_resource(
-                        texture.desc.clone().into(),
-                        AnyTransientResource::OwnedTexture(texture),
-                    );
-                }
-                _ => {}
+            self.recycle_resource(release, resource, device, transient_resource_cache);
+        }
+    }
+
+    /// Like [`ResourceTable::release_resource`], but the table keeps its copy, so
+    /// passes can still be recorded against the resource while a later pass reuses
+    /// it. Call [`ResourceTable::clear`] once recording is done.
+    pub fn release_resource_retained(
+        &mut self,
+        release: &ResourceRelease,
+        device: &RenderDevice,
+        transient_resource_cache: &mut TransientResourceCache,
+    ) {
+        if let Some(resource) = self.resources.get(&release.index).cloned() {
+            self.recycle_resource(release, resource, device, transient_resource_cache);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.resources.clear();
+    }
+
+    fn recycle_resource(
+        &mut self,
+        release: &ResourceRelease,
+        resource: AnyTransientResource,
+        device: &RenderDevice,
+        transient_resource_cache: &mut TransientResourceCache,
+    ) {
+        device.record(RenderDeviceEvent::ReleaseResource {
+            index: release.index.index,
+        });
+
+        if release.exported {
+            self.exported_resources
+                .insert(release.index, resource.into());
+            return;
+        }
+
+        match resource {
+            AnyTransientResource::OwnedBuffer(buffer) => {
+                transient_resource_cache.insert_resource(
+                    buffer.desc.clone().into(),
+                    AnyTransientResource::OwnedBuffer(buffer),
+                );
+            }
+            AnyTransientResource::OwnedTexture(texture) => {
+                transient_resource_cache.insert_resource(
+                    texture.desc.clone().into(),
+                    AnyTransientResource::OwnedTexture(texture),
+                );
             }
+            _ => {}
         }
     }
 }
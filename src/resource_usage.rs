@@ -0,0 +1,107 @@
+use core::fmt::{Display, Formatter, Result};
+
+use wgpu::{BufferUsages, TextureUsages};
+
+use crate::{
+    AnyTransientResourceDescriptor, IndexHandle, Ref, ResourceNode, TransientBuffer,
+    TransientTexture, TransientTextureView,
+};
+
+/// Usage flags a pass needs on a resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResourceUsage {
+    Buffer(BufferUsages),
+    Texture(TextureUsages),
+}
+
+impl ResourceUsage {
+    /// Flags of `self` that `other` lacks.
+    pub fn difference(&self, other: &ResourceUsage) -> ResourceUsage {
+        match (self, other) {
+            (ResourceUsage::Buffer(usage), ResourceUsage::Buffer(other)) => {
+                ResourceUsage::Buffer(*usage - *other)
+            }
+            (ResourceUsage::Texture(usage), ResourceUsage::Texture(other)) => {
+                ResourceUsage::Texture(*usage - *other)
+            }
+            _ => *self,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            ResourceUsage::Buffer(usage) => usage.is_empty(),
+            ResourceUsage::Texture(usage) => usage.is_empty(),
+        }
+    }
+}
+
+impl Display for ResourceUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ResourceUsage::Buffer(usage) => write!(f, "{usage:?}"),
+            ResourceUsage::Texture(usage) => write!(f, "{usage:?}"),
+        }
+    }
+}
+
+impl AnyTransientResourceDescriptor {
+    pub fn usage(&self) -> ResourceUsage {
+        match self {
+            AnyTransientResourceDescriptor::Buffer(desc) => ResourceUsage::Buffer(desc.usage),
+            AnyTransientResourceDescriptor::Texture(desc) => ResourceUsage::Texture(desc.usage),
+        }
+    }
+
+    /// ORs `usage` into the descriptor. Usages of the other resource kind are ignored.
+    pub fn add_usage(&mut self, usage: ResourceUsage) {
+        match (self, usage) {
+            (AnyTransientResourceDescriptor::Buffer(desc), ResourceUsage::Buffer(usage)) => {
+                desc.usage |= usage;
+            }
+            (AnyTransientResourceDescriptor::Texture(desc), ResourceUsage::Texture(usage)) => {
+                desc.usage |= usage;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Usages declared by the commands of a pass, collected when the pass is built.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct ResourceUsages {
+    usages: Vec<(IndexHandle<ResourceNode>, ResourceUsage)>,
+}
+
+impl ResourceUsages {
+    pub fn add_buffer<ViewType>(
+        &mut self,
+        buffer_ref: &Ref<TransientBuffer, ViewType>,
+        usage: BufferUsages,
+    ) {
+        self.usages
+            .push((buffer_ref.raw.index, ResourceUsage::Buffer(usage)));
+    }
+
+    pub fn add_texture<ViewType>(
+        &mut self,
+        texture_ref: &Ref<TransientTexture, ViewType>,
+        usage: TextureUsages,
+    ) {
+        self.usages
+            .push((texture_ref.raw.index, ResourceUsage::Texture(usage)));
+    }
+
+    /// Owned views are not tracked by the graph and are skipped.
+    pub fn add_texture_view(&mut self, view: &TransientTextureView, usage: TextureUsages) {
+        match view {
+            TransientTextureView::Read(desc) => self.add_texture(&desc.texture, usage),
+            TransientTextureView::Write(desc) => self.add_texture(&desc.texture, usage),
+            TransientTextureView::Owned(_) => {}
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(IndexHandle<ResourceNode>, ResourceUsage)> {
+        self.usages.iter()
+    }
+}
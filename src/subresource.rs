@@ -0,0 +1,176 @@
+use core::{
+    fmt::{Display, Formatter, Result},
+    ops::Range,
+};
+
+use wgpu::TextureDimension;
+
+use crate::{AnyTransientResourceDescriptor, gfx_base::TextureViewDescriptor};
+
+/// Mips and array layers of a texture that a read or write is scoped to, so passes
+/// touching disjoint parts of one texture are ordered and culled independently.
+/// A count of `None` extends to the last mip or layer. Buffers only have one
+/// sub-resource, so every range covers all of them.
+#[derive(Default, Clone, Copy, Hash, PartialEq, Eq, Debug)]
+pub struct SubresourceRange {
+    pub base_mip_level: u32,
+    pub mip_level_count: Option<u32>,
+    pub base_array_layer: u32,
+    pub array_layer_count: Option<u32>,
+}
+
+impl SubresourceRange {
+    pub const FULL: Self = Self {
+        base_mip_level: 0,
+        mip_level_count: None,
+        base_array_layer: 0,
+        array_layer_count: None,
+    };
+
+    pub fn mips(mips: Range<u32>) -> Self {
+        Self::FULL.with_mips(mips)
+    }
+
+    pub fn layers(layers: Range<u32>) -> Self {
+        Self::FULL.with_layers(layers)
+    }
+
+    pub fn with_mips(mut self, mips: Range<u32>) -> Self {
+        self.base_mip_level = mips.start;
+        self.mip_level_count = Some(mips.end.saturating_sub(mips.start));
+        self
+    }
+
+    pub fn with_layers(mut self, layers: Range<u32>) -> Self {
+        self.base_array_layer = layers.start;
+        self.array_layer_count = Some(layers.end.saturating_sub(layers.start));
+        self
+    }
+
+    pub fn is_full(&self) -> bool {
+        *self == Self::FULL
+    }
+
+    pub fn has_full_mips(&self) -> bool {
+        self.base_mip_level == 0 && self.mip_level_count.is_none()
+    }
+
+    pub fn has_full_layers(&self) -> bool {
+        self.base_array_layer == 0 && self.array_layer_count.is_none()
+    }
+
+    /// Narrows `desc` to this range, on each axis the range restricts.
+    pub fn apply(&self, desc: &mut TextureViewDescriptor) {
+        if !self.has_full_mips() {
+            desc.base_mip_level = self.base_mip_level;
+            desc.mip_level_count = self.mip_level_count;
+        }
+
+        if !self.has_full_layers() {
+            desc.base_array_layer = self.base_array_layer;
+            desc.array_layer_count = self.array_layer_count;
+        }
+    }
+
+    /// Flat indices of the sub-resources covered in a resource with `extent`.
+    pub(crate) fn cells(&self, extent: SubresourceExtent) -> impl Iterator<Item = usize> {
+        let layers = clamp(self.base_array_layer, self.array_layer_count, extent.layers);
+
+        clamp(self.base_mip_level, self.mip_level_count, extent.mips).flat_map(move |mip| {
+            layers
+                .clone()
+                .map(move |layer| (mip * extent.layers + layer) as usize)
+        })
+    }
+
+    /// Whether `cell`, as numbered by [`cells`](Self::cells), is part of the range.
+    pub(crate) fn contains(&self, cell: usize, extent: SubresourceExtent) -> bool {
+        let mip = cell as u32 / extent.layers;
+        let layer = cell as u32 % extent.layers;
+
+        clamp(self.base_mip_level, self.mip_level_count, extent.mips).contains(&mip)
+            && clamp(self.base_array_layer, self.array_layer_count, extent.layers).contains(&layer)
+    }
+
+    pub(crate) fn overlaps(&self, other: &Self, extent: SubresourceExtent) -> bool {
+        let mips = clamp(self.base_mip_level, self.mip_level_count, extent.mips);
+        let other_mips = clamp(other.base_mip_level, other.mip_level_count, extent.mips);
+        let layers = clamp(self.base_array_layer, self.array_layer_count, extent.layers);
+        let other_layers = clamp(
+            other.base_array_layer,
+            other.array_layer_count,
+            extent.layers,
+        );
+
+        intersects(&mips, &other_mips) && intersects(&layers, &other_layers)
+    }
+}
+
+fn clamp(base: u32, count: Option<u32>, total: u32) -> Range<u32> {
+    let end = count
+        .map_or(total, |count| base.saturating_add(count))
+        .min(total);
+
+    base.min(end)..end
+}
+
+fn intersects(a: &Range<u32>, b: &Range<u32>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+impl Display for SubresourceRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let format_range = |base: u32, count: Option<u32>| match count {
+            Some(count) => format!("{base}..{}", base + count),
+            None => format!("{base}.."),
+        };
+
+        match (self.has_full_mips(), self.has_full_layers()) {
+            (true, true) => write!(f, "all"),
+            (false, true) => write!(
+                f,
+                "mips {}",
+                format_range(self.base_mip_level, self.mip_level_count)
+            ),
+            (true, false) => write!(
+                f,
+                "layers {}",
+                format_range(self.base_array_layer, self.array_layer_count)
+            ),
+            (false, false) => write!(
+                f,
+                "mips {}, layers {}",
+                format_range(self.base_mip_level, self.mip_level_count),
+                format_range(self.base_array_layer, self.array_layer_count)
+            ),
+        }
+    }
+}
+
+/// Number of mips and array layers a resource has.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SubresourceExtent {
+    pub(crate) mips: u32,
+    pub(crate) layers: u32,
+}
+
+impl SubresourceExtent {
+    pub(crate) fn len(&self) -> usize {
+        (self.mips * self.layers) as usize
+    }
+}
+
+impl AnyTransientResourceDescriptor {
+    pub(crate) fn subresource_extent(&self) -> SubresourceExtent {
+        match self {
+            AnyTransientResourceDescriptor::Buffer(_) => SubresourceExtent { mips: 1, layers: 1 },
+            AnyTransientResourceDescriptor::Texture(desc) => SubresourceExtent {
+                mips: desc.mip_level_count.max(1),
+                layers: match desc.dimension {
+                    TextureDimension::D3 => 1,
+                    _ => desc.size.depth_or_array_layers.max(1),
+                },
+            },
+        }
+    }
+}
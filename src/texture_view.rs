@@ -1,5 +1,8 @@
+use wgpu::TextureFormat;
+
 use crate::{
-    PassContext, Ref, ResourceRead, ResourceView, ResourceWrite, TransientTexture,
+    FrameGraphError, PassContext, Ref, ResourceRead, ResourceView, ResourceWrite,
+    SubresourceRange, TransientTexture,
     gfx_base::{GpuTextureView, TextureView, TextureViewDescriptor},
 };
 
@@ -13,9 +16,32 @@ pub struct TransientTextureViewDescriptor<ViewType> {
 }
 
 impl<ViewType: ResourceView> TransientTextureViewDescriptor<ViewType> {
-    pub fn create_gpu_texture_view(&self, context: &PassContext) -> GpuTextureView {
-        let resource = context.get_resource(&self.texture);
-        resource.resource.create_view(&self.desc)
+    pub fn create_gpu_texture_view(
+        &self,
+        context: &PassContext,
+    ) -> Result<GpuTextureView, FrameGraphError> {
+        let resource = context.try_get_resource(&self.texture)?;
+
+        Ok(context
+            .texture_view_cache()
+            .get_or_create(&resource.resource, &self.get_view_desc()))
+    }
+
+    /// Mips and layers of the texture the graph tracks this view as touching.
+    pub fn subresource(&self) -> SubresourceRange {
+        self.texture.raw.subresource
+    }
+
+    /// `desc`, with the mips and layers `texture` is scoped to taking precedence
+    /// over its own.
+    pub fn get_view_desc(&self) -> TextureViewDescriptor {
+        let mut desc = self.desc.clone();
+        self.subresource().apply(&mut desc);
+        desc
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.desc.format.unwrap_or(self.texture.desc.format)
     }
 }
 
@@ -26,11 +52,23 @@ pub enum TransientTextureView {
 }
 
 impl TransientTextureView {
-    pub fn create_gpu_texture_view(&self, context: &PassContext) -> GpuTextureView {
+    pub fn create_gpu_texture_view(
+        &self,
+        context: &PassContext,
+    ) -> Result<GpuTextureView, FrameGraphError> {
         match self {
             TransientTextureView::Read(desc) => desc.create_gpu_texture_view(context),
             TransientTextureView::Write(desc) => desc.create_gpu_texture_view(context),
-            TransientTextureView::Owned(texture_view) => texture_view.value().clone(),
+            TransientTextureView::Owned(texture_view) => Ok(texture_view.value().clone()),
+        }
+    }
+
+    /// Format of the view, or `None` for owned views the graph knows nothing about.
+    pub fn format(&self) -> Option<TextureFormat> {
+        match self {
+            TransientTextureView::Read(desc) => Some(desc.format()),
+            TransientTextureView::Write(desc) => Some(desc.format()),
+            TransientTextureView::Owned(_) => None,
         }
     }
 }
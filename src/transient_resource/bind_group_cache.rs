@@ -0,0 +1,125 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    num::NonZero,
+    sync::Mutex,
+};
+
+use crate::{
+    FrameGraphError,
+    gfx_base::{
+        BindGroupLayoutId, GpuBindGroup, GpuBuffer, GpuTexture, SamplerId, TextureViewDescriptor,
+    },
+};
+
+use super::AnyTransientResource;
+
+/// A resolved binding: the physical resource behind it, and how it is bound.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) enum BindingKey {
+    Buffer {
+        buffer: GpuBuffer,
+        offset: u64,
+        size: Option<NonZero<u64>>,
+    },
+    BufferArray(Vec<(GpuBuffer, u64, Option<NonZero<u64>>)>),
+    Sampler(SamplerId),
+    SamplerArray(Vec<SamplerId>),
+    TextureView {
+        texture: GpuTexture,
+        desc: TextureViewDescriptor,
+    },
+    TextureViewArray(Vec<(GpuTexture, TextureViewDescriptor)>),
+}
+
+impl BindingKey {
+    fn references(&self, resource: &AnyTransientResource) -> bool {
+        let (buffer, texture) = match resource {
+            AnyTransientResource::OwnedBuffer(resource) => (Some(&resource.resource), None),
+            AnyTransientResource::ImportedBuffer(resource) => (Some(&resource.resource), None),
+            AnyTransientResource::OwnedTexture(resource) => (None, Some(&resource.resource)),
+            AnyTransientResource::ImportedTexture(resource) => (None, Some(&resource.resource)),
+        };
+
+        match self {
+            BindingKey::Buffer { buffer: bound, .. } => buffer == Some(bound),
+            BindingKey::BufferArray(bindings) => {
+                bindings.iter().any(|(bound, _, _)| buffer == Some(bound))
+            }
+            BindingKey::Sampler(_) | BindingKey::SamplerArray(_) => false,
+            BindingKey::TextureView { texture: bound, .. } => texture == Some(bound),
+            BindingKey::TextureViewArray(bindings) => {
+                bindings.iter().any(|(bound, _)| texture == Some(bound))
+            }
+        }
+    }
+}
+
+/// Everything a bind group is created from, except its label.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) struct BindGroupKey {
+    pub(crate) layout: BindGroupLayoutId,
+    pub(crate) entries: Vec<(u32, BindingKey)>,
+}
+
+struct CachedBindGroup {
+    bind_group: GpuBindGroup,
+    last_used_frame: u64,
+}
+
+/// Bind groups created while recording passes, keyed by layout and the physical
+/// resources they bind, so frames that get the same cached resources back reuse
+/// them. Owned by [`TransientResourceCache`](super::TransientResourceCache), which
+/// drops the bind groups of every resource it evicts and ages the rest.
+///
+/// Lookups lock, so passes recorded on several threads can share it.
+#[derive(Default)]
+pub struct BindGroupCache {
+    bind_groups: Mutex<HashMap<BindGroupKey, CachedBindGroup>>,
+    frame: u64,
+}
+
+impl BindGroupCache {
+    pub fn len(&self) -> usize {
+        self.bind_groups.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn try_get_or_create(
+        &self,
+        key: BindGroupKey,
+        create: impl FnOnce() -> Result<GpuBindGroup, FrameGraphError>,
+    ) -> Result<GpuBindGroup, FrameGraphError> {
+        let mut bind_groups = self.bind_groups.lock().unwrap();
+
+        let cached = match bind_groups.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(CachedBindGroup {
+                bind_group: create()?,
+                last_used_frame: self.frame,
+            }),
+        };
+        cached.last_used_frame = self.frame;
+
+        Ok(cached.bind_group.clone())
+    }
+
+    /// Drops every bind group that binds `resource`, so it can be freed.
+    pub(crate) fn remove_resource(&mut self, resource: &AnyTransientResource) {
+        self.bind_groups.get_mut().unwrap().retain(|key, _| {
+            !key.entries
+                .iter()
+                .any(|(_, binding)| binding.references(resource))
+        });
+    }
+
+    pub(crate) fn end_frame(&mut self, frame: u64, oldest_kept_frame: u64) {
+        self.frame = frame;
+        self.bind_groups
+            .get_mut()
+            .unwrap()
+            .retain(|_, cached| cached.last_used_frame >= oldest_kept_frame);
+    }
+}
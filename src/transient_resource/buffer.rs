@@ -13,6 +13,13 @@ impl IntoArcTransientResource for TransientBuffer {
     fn into_arc_transient_resource(self: Arc<Self>) -> ArcTransientResource {
         ArcTransientResource::Buffer(self)
     }
+
+    fn try_from_arc_transient_resource(resource: ArcTransientResource) -> Option<Arc<Self>> {
+        match resource {
+            ArcTransientResource::Buffer(resource) => Some(resource),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -24,13 +31,11 @@ pub struct TransientBuffer {
 impl TransientResource for TransientBuffer {
     type Descriptor = TransientBufferDescriptor;
 
-    fn borrow_resource(res: &AnyTransientResource) -> &Self {
+    fn try_borrow_resource(res: &AnyTransientResource) -> Option<&Self> {
         match res {
-            AnyTransientResource::OwnedBuffer(res) => res,
-            AnyTransientResource::ImportedBuffer(res) => res,
-            _ => {
-                unimplemented!()
-            }
+            AnyTransientResource::OwnedBuffer(res) => Some(res),
+            AnyTransientResource::ImportedBuffer(res) => Some(res),
+            _ => None,
         }
     }
 
@@ -103,12 +108,10 @@ impl From<TransientBufferDescriptor> for AnyTransientResourceDescriptor {
 impl TransientResourceDescriptor for TransientBufferDescriptor {
     type Resource = TransientBuffer;
 
-    fn borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> &Self {
+    fn try_borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> Option<&Self> {
         match res {
-            AnyTransientResourceDescriptor::Buffer(res) => res,
-            _ => {
-                unimplemented!()
-            }
+            AnyTransientResourceDescriptor::Buffer(res) => Some(res),
+            _ => None,
         }
     }
 }
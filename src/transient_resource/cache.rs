@@ -1,22 +1,171 @@
 use std::collections::HashMap;
 
-use super::{AnyTransientResource, AnyTransientResourceDescriptor};
+use crate::gfx_base::RenderDevice;
 
-#[derive(Default)]
+use super::{
+    AnyTransientResource, AnyTransientResourceDescriptor, ArcTransientResource, BindGroupCache,
+    TextureViewCache, history::HistoryResources,
+};
+
+pub const DEFAULT_MAX_UNUSED_FRAMES: u64 = 3;
+
+/// How buffer sizes are rounded up before they are allocated and looked up, so
+/// requests of similar sizes can share cached buffers.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufferSizeBucketing {
+    #[default]
+    Exact,
+    PowerOfTwo,
+    /// Multiples of the given page size in bytes.
+    Page(u64),
+}
+
+impl BufferSizeBucketing {
+    pub fn bucket_size(&self, size: u64) -> u64 {
+        match *self {
+            BufferSizeBucketing::Exact => size,
+            BufferSizeBucketing::PowerOfTwo if size > 0 => {
+                size.checked_next_power_of_two().unwrap_or(size)
+            }
+            BufferSizeBucketing::Page(page) if page > 0 => {
+                size.div_ceil(page).checked_mul(page).unwrap_or(size)
+            }
+            _ => size,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransientResourceCacheStats {
+    pub evicted_resources: u64,
+    pub evicted_bytes: u64,
+}
+
+struct CachedResource {
+    resource: AnyTransientResource,
+    desc: AnyTransientResourceDescriptor,
+    size: u64,
+    last_used_frame: u64,
+}
+
+/// Pool of idle transient resources, keyed by descriptor with the label dropped and
+/// buffer sizes rounded up to their bucket. A request is served by any resource in
+/// its bucket that is at least as large.
+///
+/// Call [`TransientResourceCache::end_frame`] once per frame to age the pool. Entries
+/// that stay idle for longer than `max_unused_frames` are dropped, and if a memory
+/// budget is set the least recently used entries are dropped until the idle pool
+/// fits in it.
+///
+/// The cache also owns the resource pairs behind history resources, which it swaps
+/// in `end_frame`, and the [`BindGroupCache`] and [`TextureViewCache`] of bind
+/// groups and views created from its resources.
 pub struct TransientResourceCache {
-    resources: HashMap<AnyTransientResourceDescriptor, Vec<AnyTransientResource>>,
+    resources: HashMap<AnyTransientResourceDescriptor, Vec<CachedResource>>,
+    frame: u64,
+    max_unused_frames: u64,
+    memory_budget: Option<u64>,
+    memory_usage: u64,
+    stats: TransientResourceCacheStats,
+    buffer_size_bucketing: BufferSizeBucketing,
+    history: HistoryResources,
+    bind_group_cache: BindGroupCache,
+    texture_view_cache: TextureViewCache,
+}
+
+impl Default for TransientResourceCache {
+    fn default() -> Self {
+        Self {
+            resources: Default::default(),
+            frame: 0,
+            max_unused_frames: DEFAULT_MAX_UNUSED_FRAMES,
+            memory_budget: None,
+            memory_usage: 0,
+            stats: Default::default(),
+            buffer_size_bucketing: Default::default(),
+            history: Default::default(),
+            bind_group_cache: Default::default(),
+            texture_view_cache: Default::default(),
+        }
+    }
 }
 
 impl TransientResourceCache {
+    pub fn set_max_unused_frames(&mut self, max_unused_frames: u64) {
+        self.max_unused_frames = max_unused_frames;
+    }
+
+    pub fn set_memory_budget(&mut self, memory_budget: Option<u64>) {
+        self.memory_budget = memory_budget;
+    }
+
+    pub fn set_buffer_size_bucketing(&mut self, buffer_size_bucketing: BufferSizeBucketing) {
+        self.buffer_size_bucketing = buffer_size_bucketing;
+    }
+
+    /// Descriptor to create a resource with when `desc` misses the cache: buffers are
+    /// rounded up to their bucket so they can serve later, slightly larger requests.
+    pub fn allocation_desc(
+        &self,
+        desc: &AnyTransientResourceDescriptor,
+    ) -> AnyTransientResourceDescriptor {
+        let mut desc = desc.clone();
+
+        if let AnyTransientResourceDescriptor::Buffer(desc) = &mut desc {
+            desc.size = self.buffer_size_bucketing.bucket_size(desc.size);
+        }
+
+        desc
+    }
+
+    fn cache_key(&self, desc: &AnyTransientResourceDescriptor) -> AnyTransientResourceDescriptor {
+        let mut key = self.allocation_desc(desc);
+
+        match &mut key {
+            AnyTransientResourceDescriptor::Buffer(desc) => desc.label = None,
+            AnyTransientResourceDescriptor::Texture(desc) => desc.label = None,
+        }
+
+        key
+    }
+
+    /// Bytes currently held by idle resources in the cache.
+    pub fn memory_usage(&self) -> u64 {
+        self.memory_usage
+    }
+
+    pub fn stats(&self) -> TransientResourceCacheStats {
+        self.stats
+    }
+
+    pub fn bind_group_cache(&self) -> &BindGroupCache {
+        &self.bind_group_cache
+    }
+
+    pub fn texture_view_cache(&self) -> &TextureViewCache {
+        &self.texture_view_cache
+    }
+
     pub fn get_resource(
         &mut self,
         desc: &AnyTransientResourceDescriptor,
     ) -> Option<AnyTransientResource> {
-        if let Some(entry) = self.resources.get_mut(desc) {
-            entry.pop()
-        } else {
-            None
-        }
+        let key = self.cache_key(desc);
+        let entry = self.resources.get_mut(&key)?;
+        let position = entry
+            .iter()
+            .rposition(|cached| match (&cached.desc, desc) {
+                (
+                    AnyTransientResourceDescriptor::Buffer(cached),
+                    AnyTransientResourceDescriptor::Buffer(desc),
+                ) => cached.size >= desc.size,
+                _ => true,
+            })?;
+
+        let cached = entry.remove(position);
+        self.memory_usage -= cached.size;
+
+        Some(cached.resource)
     }
 
     pub fn insert_resource(
@@ -24,10 +173,105 @@ impl TransientResourceCache {
         desc: AnyTransientResourceDescriptor,
         resource: AnyTransientResource,
     ) {
-        if let Some(entry) = self.resources.get_mut(&desc) {
-            entry.push(resource);
-        } else {
-            self.resources.insert(desc, vec![resource]);
+        let size = desc.estimated_memory_size();
+        self.memory_usage += size;
+
+        self.resources
+            .entry(self.cache_key(&desc))
+            .or_default()
+            .push(CachedResource {
+                resource,
+                desc,
+                size,
+                last_used_frame: self.frame,
+            });
+    }
+
+    pub(crate) fn get_history_resource(
+        &mut self,
+        key: &str,
+        desc: &AnyTransientResourceDescriptor,
+        previous: bool,
+        device: &RenderDevice,
+    ) -> ArcTransientResource {
+        self.history
+            .get_resource(key, desc, previous, self.frame, device, |resource| {
+                self.bind_group_cache.remove_resource(resource);
+                self.texture_view_cache.remove_resource(resource);
+            })
+    }
+
+    pub fn end_frame(&mut self) {
+        let ended_frame = self.frame;
+        self.frame += 1;
+
+        let oldest_kept_frame = self.frame.saturating_sub(self.max_unused_frames);
+        self.evict(|cached| cached.last_used_frame < oldest_kept_frame);
+        self.history
+            .end_frame(ended_frame, oldest_kept_frame, |resource| {
+                self.bind_group_cache.remove_resource(resource);
+                self.texture_view_cache.remove_resource(resource);
+            });
+
+        if let Some(memory_budget) = self.memory_budget {
+            self.evict_to_budget(memory_budget);
         }
+
+        self.bind_group_cache
+            .end_frame(self.frame, oldest_kept_frame);
+        self.texture_view_cache
+            .end_frame(self.frame, oldest_kept_frame);
+    }
+
+    /// Entries are pushed in frame order and reused from the back, so the front of
+    /// each list is its least recently used resource.
+    fn evict_to_budget(&mut self, memory_budget: u64) {
+        while self.memory_usage > memory_budget {
+            let Some(desc) = self
+                .resources
+                .iter()
+                .filter_map(|(desc, entry)| Some((entry.first()?.last_used_frame, desc)))
+                .min_by_key(|(last_used_frame, _)| *last_used_frame)
+                .map(|(_, desc)| desc.clone())
+            else {
+                break;
+            };
+
+            let entry = self.resources.get_mut(&desc).unwrap();
+            let cached = entry.remove(0);
+
+            if entry.is_empty() {
+                self.resources.remove(&desc);
+            }
+
+            self.drop_evicted(cached);
+        }
+    }
+
+    fn evict(&mut self, should_evict: impl Fn(&CachedResource) -> bool) {
+        let mut evicted = vec![];
+
+        for entry in self.resources.values_mut() {
+            let (kept, removed) = entry.drain(..).partition(|cached| !should_evict(cached));
+
+            *entry = kept;
+            evicted.extend(removed);
+        }
+
+        self.resources.retain(|_, entry| !entry.is_empty());
+
+        for cached in evicted {
+            self.drop_evicted(cached);
+        }
+    }
+
+    /// Accounts for an evicted resource and drops the bind groups and views that
+    /// would otherwise keep its memory alive.
+    fn drop_evicted(&mut self, cached: CachedResource) {
+        self.memory_usage -= cached.size;
+        self.stats.evicted_resources += 1;
+        self.stats.evicted_bytes += cached.size;
+        self.bind_group_cache.remove_resource(&cached.resource);
+        self.texture_view_cache.remove_resource(&cached.resource);
     }
 }
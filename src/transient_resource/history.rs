@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use crate::gfx_base::RenderDevice;
+
+use super::{
+    AnyTransientResource, AnyTransientResourceDescriptor, ArcTransientResource,
+    TransientResourceCreator,
+};
+
+struct HistoryEntry {
+    desc: AnyTransientResourceDescriptor,
+    resources: [ArcTransientResource; 2],
+    current: usize,
+    last_used_frame: u64,
+}
+
+impl HistoryEntry {
+    /// Hands both resources to `forget`, so whatever was built from them is freed
+    /// with the entry.
+    fn forget(&self, forget: &mut impl FnMut(&AnyTransientResource)) {
+        for resource in self.resources.iter() {
+            forget(&resource.clone().into());
+        }
+    }
+}
+
+/// Ping-ponged pairs of resources that persist across frames, keyed by the name
+/// they were created with in the graph.
+#[derive(Default)]
+pub(crate) struct HistoryResources {
+    entries: HashMap<String, HistoryEntry>,
+}
+
+impl HistoryResources {
+    /// Returns this frame's resource, or last frame's if `previous` is set. Both are
+    /// created on first use and recreated if `desc` changed since, passing the old
+    /// pair to `forget`.
+    pub(crate) fn get_resource(
+        &mut self,
+        key: &str,
+        desc: &AnyTransientResourceDescriptor,
+        previous: bool,
+        frame: u64,
+        device: &RenderDevice,
+        mut forget: impl FnMut(&AnyTransientResource),
+    ) -> ArcTransientResource {
+        let entry = match self.entries.get_mut(key) {
+            Some(entry) if &entry.desc == desc => entry,
+            replaced => {
+                if let Some(replaced) = replaced {
+                    replaced.forget(&mut forget);
+                }
+
+                let entry = HistoryEntry {
+                    desc: desc.clone(),
+                    resources: [
+                        device.create_resource(desc).into(),
+                        device.create_resource(desc).into(),
+                    ],
+                    current: 0,
+                    last_used_frame: frame,
+                };
+
+                self.entries.insert(key.to_string(), entry);
+                self.entries.get_mut(key).unwrap()
+            }
+        };
+
+        entry.last_used_frame = frame;
+
+        let index = if previous {
+            1 - entry.current
+        } else {
+            entry.current
+        };
+
+        entry.resources[index].clone()
+    }
+
+    /// Swaps the pairs used in `ended_frame`, so their resource of that frame becomes
+    /// the history of the next one, and drops pairs no graph used since
+    /// `oldest_kept_frame`, passing them to `forget`. Pairs left untouched keep their history for the next
+    /// frame that uses them.
+    pub(crate) fn end_frame(
+        &mut self,
+        ended_frame: u64,
+        oldest_kept_frame: u64,
+        mut forget: impl FnMut(&AnyTransientResource),
+    ) {
+        self.entries.retain(|_, entry| {
+            let keep = entry.last_used_frame >= oldest_kept_frame;
+
+            if !keep {
+                entry.forget(&mut forget);
+            }
+
+            keep
+        });
+
+        for entry in self
+            .entries
+            .values_mut()
+            .filter(|entry| entry.last_used_frame == ended_frame)
+        {
+            entry.current = 1 - entry.current;
+        }
+    }
+}
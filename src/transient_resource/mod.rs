@@ -1,10 +1,16 @@
+mod bind_group_cache;
 mod buffer;
 mod cache;
+mod history;
 mod texture;
+mod texture_view_cache;
 
+pub use bind_group_cache::BindGroupCache;
+pub(crate) use bind_group_cache::{BindGroupKey, BindingKey};
 pub use buffer::*;
 pub use cache::*;
 pub use texture::*;
+pub use texture_view_cache::TextureViewCache;
 
 use std::{fmt::Debug, sync::Arc};
 
@@ -58,8 +64,11 @@ impl ArcTransientResource {
 
 pub trait IntoArcTransientResource: TransientResource {
     fn into_arc_transient_resource(self: Arc<Self>) -> ArcTransientResource;
+
+    fn try_from_arc_transient_resource(resource: ArcTransientResource) -> Option<Arc<Self>>;
 }
 
+#[derive(Clone)]
 pub enum AnyTransientResource {
     OwnedBuffer(TransientBuffer),
     ImportedBuffer(Arc<TransientBuffer>),
@@ -91,16 +100,55 @@ impl From<Arc<TransientTexture>> for AnyTransientResource {
     }
 }
 
+impl From<AnyTransientResource> for ArcTransientResource {
+    fn from(value: AnyTransientResource) -> Self {
+        match value {
+            AnyTransientResource::OwnedBuffer(buffer) => {
+                ArcTransientResource::Buffer(Arc::new(buffer))
+            }
+            AnyTransientResource::ImportedBuffer(buffer) => ArcTransientResource::Buffer(buffer),
+            AnyTransientResource::OwnedTexture(texture) => {
+                ArcTransientResource::Texture(Arc::new(texture))
+            }
+            AnyTransientResource::ImportedTexture(texture) => {
+                ArcTransientResource::Texture(texture)
+            }
+        }
+    }
+}
+
+impl From<ArcTransientResource> for AnyTransientResource {
+    fn from(value: ArcTransientResource) -> Self {
+        match value {
+            ArcTransientResource::Buffer(resource) => resource.into(),
+            ArcTransientResource::Texture(resource) => resource.into(),
+        }
+    }
+}
+
 #[derive(Clone, Hash, PartialEq, Eq)]
 pub enum AnyTransientResourceDescriptor {
     Buffer(TransientBufferDescriptor),
     Texture(TransientTextureDescriptor),
 }
 
+impl AnyTransientResourceDescriptor {
+    pub fn estimated_memory_size(&self) -> u64 {
+        match self {
+            AnyTransientResourceDescriptor::Buffer(desc) => desc.size,
+            AnyTransientResourceDescriptor::Texture(desc) => desc.estimated_memory_size(),
+        }
+    }
+}
+
 pub trait TransientResource: 'static {
     type Descriptor: TransientResourceDescriptor;
 
-    fn borrow_resource(res: &AnyTransientResource) -> &Self;
+    fn try_borrow_resource(res: &AnyTransientResource) -> Option<&Self>;
+
+    fn borrow_resource(res: &AnyTransientResource) -> &Self {
+        Self::try_borrow_resource(res).expect("transient resource type mismatch")
+    }
 
     fn get_desc(&self) -> &Self::Descriptor;
 }
@@ -110,7 +158,12 @@ pub trait TransientResourceDescriptor:
 {
     type Resource: TransientResource;
 
-    fn borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> &Self;
+    fn try_borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> Option<&Self>;
+
+    fn borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> &Self {
+        Self::try_borrow_resource_descriptor(res)
+            .expect("transient resource descriptor type mismatch")
+    }
 }
 
 pub trait TypeEquals {
@@ -10,23 +10,52 @@ impl IntoArcTransientResource for TransientTexture {
     fn into_arc_transient_resource(self: Arc<Self>) -> ArcTransientResource {
         ArcTransientResource::Texture(self)
     }
+
+    fn try_from_arc_transient_resource(resource: ArcTransientResource) -> Option<Arc<Self>> {
+        match resource {
+            ArcTransientResource::Texture(resource) => Some(resource),
+            _ => None,
+        }
+    }
 }
 
+/// A texture and the descriptor it was created with. Views of it are cached by the
+/// [`TextureViewCache`](super::TextureViewCache) of the resource cache.
+#[derive(Clone)]
 pub struct TransientTexture {
     pub resource: GpuTexture,
     pub desc: TransientTextureDescriptor,
 }
 
+impl TransientTexture {
+    /// Wraps a texture created outside the graph, reading its descriptor back from it.
+    /// View formats can't be queried and are left empty.
+    pub fn from_gpu_texture(resource: GpuTexture) -> Self {
+        let texture = resource.get_wgpu_texture();
+
+        let desc = TransientTextureDescriptor {
+            label: None,
+            size: texture.size(),
+            mip_level_count: texture.mip_level_count(),
+            sample_count: texture.sample_count(),
+            dimension: texture.dimension(),
+            format: texture.format(),
+            usage: texture.usage(),
+            view_formats: vec![],
+        };
+
+        Self { resource, desc }
+    }
+}
+
 impl TransientResource for TransientTexture {
     type Descriptor = TransientTextureDescriptor;
 
-    fn borrow_resource(res: &AnyTransientResource) -> &Self {
+    fn try_borrow_resource(res: &AnyTransientResource) -> Option<&Self> {
         match res {
-            AnyTransientResource::OwnedTexture(res) => res,
-            AnyTransientResource::ImportedTexture(res) => res,
-            _ => {
-                unimplemented!()
-            }
+            AnyTransientResource::OwnedTexture(res) => Some(res),
+            AnyTransientResource::ImportedTexture(res) => Some(res),
+            _ => None,
         }
     }
 
@@ -61,9 +90,30 @@ impl TransientTextureDescriptor {
         }
     }
 
+    /// Approximate size of the texture in bytes, summed over all mips and samples.
+    pub fn estimated_memory_size(&self) -> u64 {
+        let (block_width, block_height) = self.format.block_dimensions();
+        let block_size = self.format.block_copy_size(None).unwrap_or(4) as u64;
+
+        let texel_blocks = (0..self.mip_level_count)
+            .map(|mip_level| {
+                let size = self
+                    .size
+                    .mip_level_size(mip_level, self.dimension)
+                    .physical_size(self.format);
+
+                (size.width / block_width) as u64
+                    * (size.height / block_height) as u64
+                    * size.depth_or_array_layers as u64
+            })
+            .sum::<u64>();
+
+        texel_blocks * block_size * self.sample_count as u64
+    }
+
     pub fn get_desc(&self) -> TextureDescriptor {
         TextureDescriptor {
-            label: self.label.as_ref().map(|v| v.clone().into()),
+            label: self.label.clone(),
             size: self.size,
             mip_level_count: self.mip_level_count,
             sample_count: self.sample_count,
@@ -84,12 +134,10 @@ impl From<TransientTextureDescriptor> for AnyTransientResourceDescriptor {
 impl TransientResourceDescriptor for TransientTextureDescriptor {
     type Resource = TransientTexture;
 
-    fn borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> &Self {
+    fn try_borrow_resource_descriptor(res: &AnyTransientResourceDescriptor) -> Option<&Self> {
         match res {
-            AnyTransientResourceDescriptor::Texture(res) => res,
-            _ => {
-                unimplemented!()
-            }
+            AnyTransientResourceDescriptor::Texture(res) => Some(res),
+            _ => None,
         }
     }
 }
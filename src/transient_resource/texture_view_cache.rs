@@ -0,0 +1,77 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::gfx_base::{GpuTexture, GpuTextureView, TextureViewDescriptor};
+
+use super::AnyTransientResource;
+
+struct CachedTextureView {
+    view: GpuTextureView,
+    last_used_frame: u64,
+}
+
+/// Views created while recording passes, keyed by the physical texture and the
+/// view descriptor, so attachments and bindings of the same texture share them
+/// across passes and frames. Owned by
+/// [`TransientResourceCache`](super::TransientResourceCache), which drops the views
+/// of every texture it evicts and ages the rest.
+///
+/// Lookups lock, so passes recorded on several threads can share it.
+#[derive(Default)]
+pub struct TextureViewCache {
+    views: Mutex<HashMap<GpuTexture, HashMap<TextureViewDescriptor, CachedTextureView>>>,
+    frame: u64,
+}
+
+impl TextureViewCache {
+    /// Number of views across all textures.
+    pub fn len(&self) -> usize {
+        self.views.lock().unwrap().values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the view of `texture` matching `desc`, creating it on first use.
+    pub fn get_or_create(
+        &self,
+        texture: &GpuTexture,
+        desc: &TextureViewDescriptor,
+    ) -> GpuTextureView {
+        let mut views = self.views.lock().unwrap();
+
+        let cached = views
+            .entry(texture.clone())
+            .or_default()
+            .entry(desc.clone())
+            .or_insert_with(|| CachedTextureView {
+                view: texture.create_view(desc),
+                last_used_frame: self.frame,
+            });
+        cached.last_used_frame = self.frame;
+
+        cached.view.clone()
+    }
+
+    /// Drops every view of `resource`, so it can be freed.
+    pub(crate) fn remove_resource(&mut self, resource: &AnyTransientResource) {
+        let texture = match resource {
+            AnyTransientResource::OwnedTexture(resource) => &resource.resource,
+            AnyTransientResource::ImportedTexture(resource) => &resource.resource,
+            AnyTransientResource::OwnedBuffer(_) | AnyTransientResource::ImportedBuffer(_) => {
+                return;
+            }
+        };
+
+        self.views.get_mut().unwrap().remove(texture);
+    }
+
+    pub(crate) fn end_frame(&mut self, frame: u64, oldest_kept_frame: u64) {
+        self.frame = frame;
+
+        self.views.get_mut().unwrap().retain(|_, texture_views| {
+            texture_views.retain(|_, cached| cached.last_used_frame >= oldest_kept_frame);
+            !texture_views.is_empty()
+        });
+    }
+}
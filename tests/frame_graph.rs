@@ -0,0 +1,2456 @@
+use std::{
+    collections::HashSet,
+    num::NonZero,
+    panic::{AssertUnwindSafe, catch_unwind},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, Thread},
+    time::Duration,
+};
+
+use frame_graph::{
+    BindlessBufferTable, BufferSizeBucketing, ClearBuffer, ClearTexture, CompileDiagnostic,
+    CompileError, CopyBufferToBuffer, CopyBufferToTexture, CopyTextureToBuffer,
+    CopyTextureToTexture, FrameGraph, FrameGraphContext, FrameGraphError, GraphRawResourceHandle,
+    Handle, IndexHandle, PassBuilder, PassCommand, PassContext, PassNodeBuilder,
+    PassNodeBuilderExt, RecordingThreadPool, Ref, ResourceNode, ResourceRead, ResourceUsage,
+    ResourceWrite, SubresourceRange, TextureViewCache, TransientBindGroup,
+    TransientBindGroupBuffer, TransientBindGroupEntry, TransientBindGroupResource,
+    TransientBindGroupStorageBuffer, TransientBindGroupStorageTextureView,
+    TransientBindGroupTextureView, TransientBuffer, TransientBufferDescriptor,
+    TransientMemoryReport, TransientRenderPassColorAttachment,
+    TransientRenderPassDepthStencilAttachment, TransientResourceCache,
+    TransientTexelCopyBufferInfo, TransientTexelCopyTextureInfo, TransientTexture,
+    TransientTextureDescriptor, TransientTextureView, TransientTextureViewDescriptor,
+    VirtualResource,
+    gfx_base::{
+        BindGroupLayout, BindGroupLayoutDescriptor, FragmentState, GpuBuffer, GpuPipeline,
+        GpuSurfaceTexture, PipelineContainer, RenderDevice, RenderDeviceEvent,
+        RenderDeviceRecorder, RenderPipelineDescriptor, RenderQueue, Sampler, SamplerDescriptor,
+        TextureDescriptor, TextureViewDescriptor, VertexBufferLayout, VertexState,
+    },
+};
+use wgpu::{
+    BufferUsages, Extent3d, ImageSubresourceRange, LoadOp, Operations, StoreOp, TextureDimension,
+    TextureFormat, TextureUsages,
+};
+
+fn create_render_device() -> (RenderDevice, wgpu::Queue, RenderDeviceRecorder) {
+    let (device, queue) = wgpu::Device::noop(&wgpu::DeviceDescriptor {
+        required_features: wgpu::Features::CLEAR_TEXTURE
+            | wgpu::Features::BUFFER_BINDING_ARRAY
+            | wgpu::Features::TEXTURE_BINDING_ARRAY
+            | wgpu::Features::STORAGE_RESOURCE_BINDING_ARRAY
+            | wgpu::Features::PARTIALLY_BOUND_BINDING_ARRAY,
+        required_limits: wgpu::Limits {
+            max_binding_array_elements_per_shader_stage: 16,
+            max_binding_array_sampler_elements_per_shader_stage: 16,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let recorder = RenderDeviceRecorder::default();
+
+    (
+        RenderDevice::new(device).with_recorder(recorder.clone()),
+        queue,
+        recorder,
+    )
+}
+
+fn buffer_desc(label: &'static str) -> TransientBufferDescriptor {
+    TransientBufferDescriptor {
+        label: Some(label.into()),
+        size: 256,
+        usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    }
+}
+
+fn texture_desc(label: &'static str) -> TransientTextureDescriptor {
+    TransientTextureDescriptor {
+        label: Some(label.into()),
+        size: Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: TextureUsages::empty(),
+        view_formats: vec![],
+    }
+}
+
+fn import_output(graph: &mut FrameGraph, device: &RenderDevice) -> Handle<TransientBuffer> {
+    let desc = buffer_desc("output");
+    let buffer = TransientBuffer {
+        resource: device.create_buffer(&desc.get_buffer_desc()),
+        desc,
+    };
+
+    graph.import("output", Arc::new(buffer))
+}
+
+fn clear_pass(
+    graph: &mut FrameGraph,
+    name: &str,
+    buffer: Handle<TransientBuffer>,
+) -> Handle<TransientBuffer> {
+    let mut builder = PassBuilder::new(PassNodeBuilder::new(name, graph));
+    let buffer = builder.write(buffer);
+
+    builder.push(ClearBuffer {
+        buffer: buffer.clone(),
+        offset: 0,
+        size: None,
+    });
+
+    buffer.handle()
+}
+
+fn clear_texture_pass(
+    graph: &mut FrameGraph,
+    name: &str,
+    texture: Handle<TransientTexture>,
+) -> Handle<TransientTexture> {
+    let mut builder = PassBuilder::new(PassNodeBuilder::new(name, graph));
+    let texture = builder.write(texture);
+
+    builder.push(ClearTexture {
+        texture: texture.clone(),
+        subresource_range: ImageSubresourceRange::default(),
+    });
+
+    texture.handle()
+}
+
+fn copy_pass(
+    graph: &mut FrameGraph,
+    name: &str,
+    source: Handle<TransientBuffer>,
+    destination: Handle<TransientBuffer>,
+) -> Handle<TransientBuffer> {
+    let mut builder = PassBuilder::new(PassNodeBuilder::new(name, graph));
+    let source = builder.read(source);
+    let destination = builder.write(destination);
+
+    builder.push(CopyBufferToBuffer {
+        source,
+        source_offset: 0,
+        destination: destination.clone(),
+        destination_offset: 0,
+        size: None,
+    });
+
+    destination.handle()
+}
+
+fn execute(
+    graph: &mut FrameGraph,
+    device: &RenderDevice,
+    cache: &mut TransientResourceCache,
+) -> Result<(), FrameGraphError> {
+    graph.compile()?;
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), device, cache);
+    graph.execute(&mut context)
+}
+
+fn executed_passes(events: &[RenderDeviceEvent]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            RenderDeviceEvent::ExecutePass { name } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn created_buffers(events: &[RenderDeviceEvent], label: &str) -> usize {
+    events
+        .iter()
+        .filter(|event| {
+            matches!(event, RenderDeviceEvent::CreateBuffer { label: Some(created), .. } if created == label)
+        })
+        .count()
+}
+
+#[test]
+fn culls_passes_without_consumers() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let unused = graph.create("unused", buffer_desc("unused"));
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    clear_pass(&mut graph, "dead", unused);
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(executed_passes(&events), ["clear", "copy"]);
+    assert_eq!(created_buffers(&events, "unused"), 0);
+    assert_eq!(created_buffers(&events, "scratch"), 1);
+}
+
+#[test]
+fn culls_producers_of_culled_passes() {
+    let mut graph = FrameGraph::default();
+
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    let temporary = graph.create("temporary", buffer_desc("temporary"));
+
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, temporary);
+
+    graph.compile().unwrap();
+
+    let culled = (0..2)
+        .map(|index| graph.get_pass_node(&IndexHandle::new(index)).culled)
+        .collect::<Vec<_>>();
+
+    assert_eq!(culled, [true, true]);
+}
+
+fn next_version(handle: &Handle<TransientBuffer>) -> Handle<TransientBuffer> {
+    Handle::new(
+        handle.raw.index,
+        handle.raw.version + 1,
+        handle.desc.clone(),
+    )
+}
+
+#[test]
+fn sorts_passes_by_dependencies_instead_of_registration_order() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    copy_pass(&mut graph, "copy", next_version(&scratch), output);
+    clear_pass(&mut graph, "clear", scratch);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(executed_passes(&recorder.take_events()), ["clear", "copy"]);
+}
+
+#[test]
+fn reports_dependency_cycles() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    let output = copy_pass(&mut graph, "first", next_version(&scratch), output);
+    copy_pass(&mut graph, "second", output.clone(), scratch);
+    clear_pass(&mut graph, "downstream", output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Compile(CompileError::Cycle {
+            passes: vec!["first".into(), "second".into()],
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        "frame graph has a dependency cycle between passes: first, second"
+    );
+}
+
+#[test]
+fn keeps_side_effect_passes() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    {
+        let mut builder = PassBuilder::new(PassNodeBuilder::new("side_effect", &mut graph));
+        let buffer = builder.write(scratch);
+        builder.make_side_effect();
+        builder.push(ClearBuffer {
+            buffer,
+            offset: 0,
+            size: None,
+        });
+    }
+
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(executed_passes(&recorder.events()), ["side_effect"]);
+}
+
+#[test]
+fn reuses_released_resources_within_a_frame() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let first = graph.create("first", buffer_desc("scratch"));
+    let second = graph.create("second", buffer_desc("scratch"));
+
+    let first = clear_pass(&mut graph, "clear_first", first);
+    let output = copy_pass(&mut graph, "copy_first", first, output);
+    let second = clear_pass(&mut graph, "clear_second", second);
+    copy_pass(&mut graph, "copy_second", second, output);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(created_buffers(&events, "scratch"), 1);
+    assert!(events.contains(&RenderDeviceEvent::ReuseResource { index: 2 }));
+    assert_eq!(
+        executed_passes(&events),
+        ["clear_first", "copy_first", "clear_second", "copy_second"]
+    );
+}
+
+#[test]
+fn reuses_cached_resources_across_frames() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    for _ in 0..2 {
+        let mut graph = FrameGraph::default();
+        let output = import_output(&mut graph, &device);
+        let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+        let scratch = clear_pass(&mut graph, "clear", scratch);
+        copy_pass(&mut graph, "copy", scratch, output);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    let events = recorder.events();
+    assert_eq!(created_buffers(&events, "scratch"), 1);
+    assert!(events.contains(&RenderDeviceEvent::ReuseResource { index: 1 }));
+}
+
+#[test]
+fn evicts_unused_cache_entries() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    cache.set_max_unused_frames(1);
+
+    let mut graph = FrameGraph::default();
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    execute(&mut graph, &device, &mut cache).unwrap();
+    assert_eq!(cache.memory_usage(), 256);
+
+    cache.end_frame();
+    assert_eq!(cache.memory_usage(), 256);
+
+    cache.end_frame();
+    assert_eq!(cache.memory_usage(), 0);
+    assert_eq!(cache.stats().evicted_resources, 1);
+    assert_eq!(cache.stats().evicted_bytes, 256);
+}
+
+#[test]
+fn evicts_down_to_memory_budget() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    cache.set_memory_budget(Some(0));
+
+    let mut graph = FrameGraph::default();
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    execute(&mut graph, &device, &mut cache).unwrap();
+    cache.end_frame();
+
+    assert_eq!(cache.memory_usage(), 0);
+    assert_eq!(cache.stats().evicted_resources, 1);
+}
+
+#[test]
+fn reports_uninitialized_reads() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Compile(CompileError::Invalid {
+            diagnostics: vec![CompileDiagnostic::ReadUninitialized {
+                pass: "copy".into(),
+                resource: "scratch".into(),
+                version: 0,
+            }],
+        })
+    );
+}
+
+#[test]
+fn reports_stale_reads() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    let first = clear_pass(&mut graph, "clear_first", scratch);
+    clear_pass(&mut graph, "clear_second", first.clone());
+    copy_pass(&mut graph, "copy", first, output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Compile(CompileError::Invalid {
+            diagnostics: vec![CompileDiagnostic::ReadStaleVersion {
+                pass: "copy".into(),
+                resource: "scratch".into(),
+                version: 1,
+                latest_version: 2,
+            }],
+        })
+    );
+}
+
+#[test]
+fn renders_every_diagnostic_of_invalid_graphs() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    let staging = graph.create("staging", buffer_desc("staging"));
+
+    let output = copy_pass(&mut graph, "copy_scratch", scratch, output);
+    let first = clear_pass(&mut graph, "clear_first", staging);
+    clear_pass(&mut graph, "clear_second", first.clone());
+    copy_pass(&mut graph, "copy_staging", first, output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "frame graph is invalid:\n  \
+         pass `copy_scratch` reads version 0 of `scratch`, which no pass writes\n  \
+         pass `copy_staging` reads version 1 of `staging`, but version 2 was already written"
+    );
+}
+
+#[test]
+fn recovers_from_frames_that_fail_to_compile() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    copy_pass(&mut graph, "bad_copy", scratch, output);
+
+    assert!(matches!(
+        execute(&mut graph, &device, &mut cache),
+        Err(FrameGraphError::Compile(CompileError::Invalid { .. }))
+    ));
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(executed_passes(&recorder.take_events()), ["clear", "copy"]);
+}
+
+#[test]
+fn reports_graphs_executed_without_compiling() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    clear_pass(&mut graph, "clear", output);
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    assert_eq!(
+        graph.execute(&mut context),
+        Err(FrameGraphError::NotCompiled)
+    );
+
+    let output = import_output(&mut graph, &device);
+    clear_pass(&mut graph, "clear", output);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(executed_passes(&recorder.take_events()), ["clear"]);
+}
+
+#[test]
+fn skips_passes_with_missing_pipelines() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+
+    {
+        let mut builder = PassBuilder::new(PassNodeBuilder::new("compute", &mut graph));
+        builder.make_side_effect();
+        builder
+            .create_compute_pass_builder("compute")
+            .set_compute_pipeline(0)
+            .dispatch_workgroups(1, 1, 1)
+            .create_compute_pass_builder();
+    }
+
+    clear_pass(&mut graph, "clear", output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Execute {
+            errors: vec![FrameGraphError::Pass {
+                pass: "compute".into(),
+                source: Box::new(FrameGraphError::MissingComputePipeline { id: 0 }),
+            }],
+        }
+    );
+    assert_eq!(executed_passes(&recorder.events()), ["compute", "clear"]);
+}
+
+#[test]
+fn exports_compiled_graph_as_dot() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let unused = graph.create("unused", buffer_desc("unused"));
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+    clear_pass(&mut graph, "dead", unused);
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    let history = graph.create_history("history", buffer_desc("history"));
+    copy_pass(&mut graph, "accumulate", history.previous, history.current);
+
+    graph.compile().unwrap();
+
+    let dot = graph.compiled_frame_graph().unwrap().to_dot();
+    assert_eq!(dot, graph.to_dot());
+    assert!(dot.starts_with("digraph FrameGraph {"));
+    assert!(dot.contains("p0 [label=\"dead\", shape=box, style=dashed"));
+    assert!(dot.contains("p1 [label=\"#0 clear\", shape=box];"));
+    assert!(dot.contains("label=\"scratch\\nclear .. copy\";"));
+    assert!(dot.contains("r2_v1 -> p2;"));
+    assert!(dot.contains("p2 -> r0_v1;"));
+    assert_eq!(dot.matches("color=lightblue;").count(), 1);
+    assert_eq!(dot.matches("color=palegreen;").count(), 2);
+}
+
+#[test]
+fn reuses_compiled_graph_in_retained_mode() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+    graph.set_retained(true);
+
+    let mut reused = vec![];
+
+    for frame in 0..3 {
+        let output = import_output(&mut graph, &device);
+        let scratch = graph.create("scratch", buffer_desc("scratch"));
+
+        let scratch = clear_pass(&mut graph, "clear", scratch);
+        let output = copy_pass(&mut graph, "copy", scratch, output);
+
+        if frame == 2 {
+            clear_pass(&mut graph, "clear_output", output);
+        }
+
+        graph.compile().unwrap();
+        reused.push(graph.compiled_frame_graph().unwrap().is_reused());
+
+        let mut context =
+            FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+        graph.execute(&mut context).unwrap();
+    }
+
+    assert_eq!(reused, [false, true, false]);
+    assert_eq!(
+        executed_passes(&recorder.events()),
+        [
+            "clear",
+            "copy",
+            "clear",
+            "copy",
+            "clear",
+            "copy",
+            "clear_output"
+        ]
+    );
+}
+
+fn inferred_usage(graph: &FrameGraph, index: IndexHandle<ResourceNode>) -> ResourceUsage {
+    match &graph.get_resource_node(&index).resource {
+        VirtualResource::Setuped(desc) => desc.usage(),
+        _ => panic!("expected a transient resource"),
+    }
+}
+
+fn created_textures(events: &[RenderDeviceEvent], label: &str) -> usize {
+    events
+        .iter()
+        .filter(|event| {
+            matches!(event, RenderDeviceEvent::CreateTexture { label: Some(created) } if created == label)
+        })
+        .count()
+}
+
+fn texel_copy_texture<ViewType>(
+    texture: Ref<TransientTexture, ViewType>,
+) -> TransientTexelCopyTextureInfo<ViewType> {
+    TransientTexelCopyTextureInfo {
+        texture,
+        mip_level: 0,
+        origin: wgpu::Origin3d::ZERO,
+        aspect: wgpu::TextureAspect::All,
+    }
+}
+
+fn texel_copy_buffer<ViewType>(
+    buffer: Ref<TransientBuffer, ViewType>,
+) -> TransientTexelCopyBufferInfo<ViewType> {
+    TransientTexelCopyBufferInfo {
+        buffer,
+        layout: wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(256),
+            rows_per_image: None,
+        },
+    }
+}
+
+const ROW: Extent3d = Extent3d {
+    width: 64,
+    height: 1,
+    depth_or_array_layers: 1,
+};
+
+#[test]
+fn uploads_buffers_into_textures() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let staging = graph.create(
+        "staging",
+        TransientBufferDescriptor {
+            usage: BufferUsages::empty(),
+            ..buffer_desc("staging")
+        },
+    );
+    let albedo = graph.create("albedo", texture_desc("albedo"));
+    let unused = graph.create("unused", texture_desc("unused"));
+    let target = graph.create("target", texture_desc("target"));
+    let indices = [staging.raw.index, albedo.raw.index, target.raw.index];
+
+    let staging = clear_pass(&mut graph, "clear", staging);
+
+    let [albedo, _] = [("upload", albedo), ("dead_upload", unused)].map(|(name, texture)| {
+        let mut builder = PassBuilder::new(PassNodeBuilder::new(name, &mut graph));
+        let source = builder.read(staging.clone());
+        let destination = builder.write(texture);
+        builder.push(CopyBufferToTexture {
+            source: texel_copy_buffer(source),
+            destination: texel_copy_texture(destination.clone()),
+            copy_size: ROW,
+        });
+
+        destination.handle()
+    });
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("blit", &mut graph));
+    let source = builder.read(albedo);
+    let destination = builder.write(target);
+    builder.push(CopyTextureToTexture {
+        source: texel_copy_texture(source),
+        destination: texel_copy_texture(destination),
+        copy_size: ROW,
+    });
+    builder.make_side_effect();
+    drop(builder);
+
+    graph.compile().unwrap();
+
+    assert_eq!(
+        indices.map(|index| inferred_usage(&graph, index)),
+        [
+            ResourceUsage::Buffer(BufferUsages::COPY_DST | BufferUsages::COPY_SRC),
+            ResourceUsage::Texture(TextureUsages::COPY_DST | TextureUsages::COPY_SRC),
+            ResourceUsage::Texture(TextureUsages::COPY_DST),
+        ]
+    );
+
+    recorder.take_events();
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(executed_passes(&events), ["clear", "upload", "blit"]);
+    assert_eq!(created_textures(&events, "unused"), 0);
+}
+
+#[test]
+fn reads_textures_back_into_buffers() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let readback = import_output(&mut graph, &device);
+    let color = graph.create("color", texture_desc("color"));
+    let unused = graph.create("unused", texture_desc("unused"));
+    let index = color.raw.index;
+
+    let color = clear_texture_pass(&mut graph, "clear", color);
+    clear_texture_pass(&mut graph, "dead_clear", unused);
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("readback", &mut graph));
+    let source = builder.read(color);
+    let destination = builder.write(readback);
+    builder.push(CopyTextureToBuffer {
+        source: texel_copy_texture(source),
+        destination: texel_copy_buffer(destination),
+        copy_size: ROW,
+    });
+    drop(builder);
+
+    graph.compile().unwrap();
+
+    assert_eq!(
+        inferred_usage(&graph, index),
+        ResourceUsage::Texture(TextureUsages::COPY_DST | TextureUsages::COPY_SRC)
+    );
+
+    recorder.take_events();
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(executed_passes(&events), ["clear", "readback"]);
+    assert_eq!(created_textures(&events, "unused"), 0);
+}
+
+#[test]
+fn infers_usages_of_transient_resources() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create(
+        "scratch",
+        TransientBufferDescriptor {
+            usage: BufferUsages::empty(),
+            ..buffer_desc("scratch")
+        },
+    );
+    let index = scratch.raw.index;
+
+    let scratch = clear_pass(&mut graph, "clear", scratch);
+    copy_pass(&mut graph, "copy", scratch, output);
+
+    graph.compile().unwrap();
+
+    let VirtualResource::Setuped(desc) = &graph.get_resource_node(&index).resource else {
+        panic!("scratch must be a transient resource");
+    };
+    assert_eq!(
+        desc.usage(),
+        ResourceUsage::Buffer(BufferUsages::COPY_SRC | BufferUsages::COPY_DST)
+    );
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+}
+
+#[test]
+fn infers_usages_of_read_only_bindings_from_layouts() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::ReadOnly,
+                        format: TextureFormat::R32Float,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        }));
+
+    let staging = graph.create("staging", buffer_desc("staging"));
+    let uniforms = graph.create(
+        "uniforms",
+        TransientBufferDescriptor {
+            usage: BufferUsages::empty(),
+            ..buffer_desc("uniforms")
+        },
+    );
+    let heights = graph.create(
+        "heights",
+        TransientTextureDescriptor {
+            format: TextureFormat::R32Float,
+            ..texture_desc("heights")
+        },
+    );
+    let uniforms_index = uniforms.raw.index;
+    let heights_index = heights.raw.index;
+
+    let staging = clear_pass(&mut graph, "clear", staging);
+    let uniforms = copy_pass(&mut graph, "upload", staging, uniforms);
+    let heights = clear_texture_pass(&mut graph, "clear_heights", heights);
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("simulate", &mut graph));
+    let bind_group = TransientBindGroup {
+        label: Some("simulate".into()),
+        layout,
+        entries: vec![
+            TransientBindGroupEntry {
+                binding: 0,
+                resource: TransientBindGroupResource::Buffer(TransientBindGroupBuffer {
+                    buffer: builder.read(uniforms),
+                    size: None,
+                    offset: 0,
+                }),
+            },
+            TransientBindGroupEntry {
+                binding: 1,
+                resource: TransientBindGroupResource::TextureView(TransientBindGroupTextureView {
+                    texture: builder.read(heights),
+                    texture_view_desc: TextureViewDescriptor::default(),
+                }),
+            },
+        ],
+    };
+    builder
+        .create_compute_pass_builder("simulate")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    graph.compile().unwrap();
+
+    let usage = |index| match &graph.get_resource_node(&index).resource {
+        VirtualResource::Setuped(desc) => desc.usage(),
+        _ => panic!("expected a transient resource"),
+    };
+    assert_eq!(
+        usage(uniforms_index),
+        ResourceUsage::Buffer(BufferUsages::COPY_DST | BufferUsages::UNIFORM)
+    );
+    assert_eq!(
+        usage(heights_index),
+        ResourceUsage::Texture(TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING)
+    );
+
+    recorder.take_events();
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    assert_eq!(
+        executed_passes(&recorder.take_events()),
+        ["clear", "upload", "clear_heights", "simulate"]
+    );
+}
+
+fn draw_pipeline(device: &RenderDevice) -> GpuPipeline {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(
+            "@vertex
+            fn vs(@location(0) position: vec2<f32>) -> @builtin(position) vec4<f32> {
+                return vec4<f32>(position, 0.0, 1.0);
+            }
+
+            @fragment
+            fn fs() -> @location(0) vec4<f32> {
+                return vec4<f32>(1.0);
+            }"
+            .into(),
+        ),
+    });
+
+    GpuPipeline::RenderPipeline(device.create_render_pipeline(RenderPipelineDescriptor {
+        label: Some("draw".into()),
+        layout: None,
+        vertex: VertexState {
+            module: module.clone(),
+            entry_point: Some("vs".into()),
+            buffers: vec![VertexBufferLayout {
+                array_stride: 8,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: wgpu::vertex_attr_array![0 => Float32x2].to_vec(),
+            }],
+            compilation_options: Default::default(),
+        },
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        fragment: Some(FragmentState {
+            module,
+            entry_point: Some("fs".into()),
+            targets: vec![Some(TextureFormat::Rgba8Unorm.into())],
+            compilation_options: Default::default(),
+        }),
+    }))
+}
+
+#[test]
+fn infers_usages_of_draw_commands() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let buffers = ["vertices", "indices", "indirect"].map(|name| {
+        let buffer = graph.create(
+            name,
+            TransientBufferDescriptor {
+                usage: BufferUsages::empty(),
+                ..buffer_desc(name)
+            },
+        );
+        let index = buffer.raw.index;
+        (index, clear_pass(&mut graph, name, buffer))
+    });
+    let color = graph.create("color", texture_desc("color"));
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("draw", &mut graph));
+    let [vertices, indices, indirect] = buffers.clone().map(|(_, buffer)| builder.read(buffer));
+    let color = builder.write(color);
+    builder
+        .create_render_pass_builder("draw")
+        .add_color_attachment(TransientRenderPassColorAttachment {
+            view: TransientTextureView::Write(TransientTextureViewDescriptor {
+                texture: color,
+                desc: TextureViewDescriptor::default(),
+            }),
+            depth_slice: None,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(wgpu::Color::BLACK),
+                store: StoreOp::Store,
+            },
+        })
+        .set_render_pipeline(0)
+        .set_vertex_buffer(0, &vertices, 0, 256)
+        .set_index_buffer(&indices, wgpu::IndexFormat::Uint16, 0, 256)
+        .draw(0..3, 0..1)
+        .draw_indexed(0..3, 0, 0..1)
+        .draw_indirect(&indirect, 0)
+        .draw_indexed_indirect(&indirect, 16)
+        .create_render_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    graph.compile().unwrap();
+
+    let usages = buffers.map(
+        |(index, _)| match &graph.get_resource_node(&index).resource {
+            VirtualResource::Setuped(desc) => desc.usage(),
+            _ => panic!("expected a transient resource"),
+        },
+    );
+    assert_eq!(
+        usages,
+        [
+            BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            BufferUsages::INDEX | BufferUsages::COPY_DST,
+            BufferUsages::INDIRECT | BufferUsages::COPY_DST,
+        ]
+        .map(ResourceUsage::Buffer)
+    );
+
+    recorder.take_events();
+    let mut context = FrameGraphContext::new(
+        PipelineContainer::new(vec![Some(draw_pipeline(&device))]),
+        &device,
+        &mut cache,
+    );
+    graph.execute(&mut context).unwrap();
+
+    assert_eq!(
+        executed_passes(&recorder.take_events()),
+        ["vertices", "indices", "indirect", "draw"]
+    );
+}
+
+#[test]
+fn keeps_inferred_usages_in_retained_mode() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+    graph.set_retained(true);
+
+    let mut reused = vec![];
+
+    for _ in 0..2 {
+        let output = import_output(&mut graph, &device);
+        let scratch = graph.create(
+            "scratch",
+            TransientBufferDescriptor {
+                usage: BufferUsages::empty(),
+                ..buffer_desc("scratch")
+            },
+        );
+
+        let scratch = clear_pass(&mut graph, "clear", scratch);
+        copy_pass(&mut graph, "copy", scratch, output);
+
+        graph.compile().unwrap();
+        reused.push(graph.compiled_frame_graph().unwrap().is_reused());
+
+        let mut context =
+            FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+        graph.execute(&mut context).unwrap();
+        cache.end_frame();
+    }
+
+    assert_eq!(reused, [false, true]);
+    assert_eq!(
+        executed_passes(&recorder.events()),
+        ["clear", "copy", "clear", "copy"]
+    );
+    assert_eq!(created_buffers(&recorder.events(), "scratch"), 1);
+}
+
+#[test]
+fn reports_missing_usages_on_imported_resources() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let desc = TransientBufferDescriptor {
+        usage: BufferUsages::COPY_SRC,
+        ..buffer_desc("output")
+    };
+    let output = graph.import(
+        "output",
+        Arc::new(TransientBuffer {
+            resource: device.create_buffer(&desc.get_buffer_desc()),
+            desc,
+        }),
+    );
+    clear_pass(&mut graph, "clear", output);
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Compile(CompileError::Invalid {
+            diagnostics: vec![CompileDiagnostic::MissingImportedUsage {
+                pass: "clear".into(),
+                resource: "output".into(),
+                usage: ResourceUsage::Buffer(BufferUsages::COPY_DST),
+            }],
+        })
+    );
+}
+
+fn depth_pass(
+    graph: &mut FrameGraph,
+    name: &str,
+    depth: Handle<TransientTexture>,
+    write: bool,
+    depth_ops: Operations<f32>,
+) {
+    let mut builder = PassBuilder::new(PassNodeBuilder::new(name, graph));
+
+    let view = if write {
+        TransientTextureView::Write(TransientTextureViewDescriptor {
+            texture: builder.write(depth),
+            desc: TextureViewDescriptor::default(),
+        })
+    } else {
+        TransientTextureView::Read(TransientTextureViewDescriptor {
+            texture: builder.read(depth),
+            desc: TextureViewDescriptor::default(),
+        })
+    };
+
+    builder
+        .create_render_pass_builder(name)
+        .set_depth_stencil_attachment(TransientRenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(depth_ops),
+            stencil_ops: None,
+        })
+        .create_render_pass_builder();
+}
+
+#[test]
+fn chooses_depth_attachment_access_from_its_ops() {
+    let mut graph = FrameGraph::default();
+
+    let depth = graph.create(
+        "depth",
+        TransientTextureDescriptor {
+            format: TextureFormat::Depth32Float,
+            ..texture_desc("depth")
+        },
+    );
+    let depth_v1 = Handle::new(depth.raw.index, 1, depth.desc.clone());
+
+    depth_pass(
+        &mut graph,
+        "prepass",
+        depth,
+        true,
+        Operations {
+            load: LoadOp::Clear(1.0),
+            store: StoreOp::Store,
+        },
+    );
+    depth_pass(
+        &mut graph,
+        "depth_test",
+        depth_v1.clone(),
+        true,
+        Operations {
+            load: LoadOp::Load,
+            store: StoreOp::Discard,
+        },
+    );
+    depth_pass(
+        &mut graph,
+        "decals",
+        depth_v1,
+        false,
+        Operations {
+            load: LoadOp::Load,
+            store: StoreOp::Store,
+        },
+    );
+
+    let versions = |handles: &[GraphRawResourceHandle]| {
+        handles
+            .iter()
+            .map(|handle| handle.version)
+            .collect::<Vec<_>>()
+    };
+    let accesses = |index: usize| {
+        let pass_node = graph.get_pass_node(&IndexHandle::new(index));
+        (versions(&pass_node.reads), versions(&pass_node.writes))
+    };
+
+    assert_eq!(accesses(0), (vec![], vec![1]));
+    assert_eq!(accesses(1), (vec![1], vec![]));
+    assert_eq!(accesses(2), (vec![1], vec![3]));
+
+    graph.compile().unwrap();
+}
+
+#[test]
+fn reports_depth_stencil_attachments_without_depth_format() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let color = graph.create("color", texture_desc("color"));
+    depth_pass(
+        &mut graph,
+        "prepass",
+        color,
+        true,
+        Operations {
+            load: LoadOp::Clear(1.0),
+            store: StoreOp::Store,
+        },
+    );
+
+    let error = execute(&mut graph, &device, &mut cache).unwrap_err();
+
+    assert_eq!(
+        error,
+        FrameGraphError::Compile(CompileError::Invalid {
+            diagnostics: vec![CompileDiagnostic::InvalidDepthStencilFormat {
+                pass: "prepass".into(),
+                format: TextureFormat::Rgba8Unorm,
+            }],
+        })
+    );
+}
+
+fn aliasing_frame(graph: &mut FrameGraph, device: &RenderDevice) {
+    let output = import_output(graph, device);
+    let first = graph.create("first", buffer_desc("first"));
+    let second = graph.create(
+        "second",
+        TransientBufferDescriptor {
+            size: 128,
+            ..buffer_desc("second")
+        },
+    );
+
+    let first = clear_pass(graph, "clear_first", first);
+    let output = copy_pass(graph, "copy_first", first, output);
+    let second = clear_pass(graph, "clear_second", second);
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("copy_second", graph));
+    let source = builder.read(second);
+    let destination = builder.write(output);
+
+    builder.push(CopyBufferToBuffer {
+        source,
+        source_offset: 0,
+        destination,
+        destination_offset: 0,
+        size: Some(128),
+    });
+}
+
+#[test]
+fn aliases_resources_with_disjoint_lifetimes() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+    graph.set_memory_aliasing(true);
+
+    aliasing_frame(&mut graph, &device);
+
+    recorder.take_events();
+    graph.compile().unwrap();
+
+    assert_eq!(
+        graph.compiled_frame_graph().unwrap().memory_report(),
+        &TransientMemoryReport {
+            peak_without_aliasing: 384,
+            peak_with_aliasing: 256,
+            resource_count: 2,
+            slot_count: 1,
+        }
+    );
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(created_buffers(&events, "first"), 1);
+    assert_eq!(created_buffers(&events, "second"), 0);
+}
+
+#[test]
+fn keeps_aliased_descriptors_in_retained_mode() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+    graph.set_memory_aliasing(true);
+    graph.set_retained(true);
+
+    for _ in 0..2 {
+        aliasing_frame(&mut graph, &device);
+
+        recorder.take_events();
+        graph.compile().unwrap();
+
+        let mut context =
+            FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+        graph.execute(&mut context).unwrap();
+        cache.end_frame();
+
+        let events = recorder.take_events();
+        assert_eq!(created_buffers(&events, "second"), 0);
+    }
+
+    assert_eq!(cache.memory_usage(), 256);
+}
+
+#[test]
+fn matches_cached_buffers_by_bucket_ignoring_labels() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    cache.set_buffer_size_bucketing(BufferSizeBucketing::PowerOfTwo);
+
+    for (label, size) in [("first", 1000), ("second", 1020)] {
+        let mut graph = FrameGraph::default();
+        let scratch = graph.create(
+            label,
+            TransientBufferDescriptor {
+                size,
+                ..buffer_desc(label)
+            },
+        );
+
+        let mut builder = PassBuilder::new(PassNodeBuilder::new(label, &mut graph));
+        let buffer = builder.write(scratch);
+        builder.make_side_effect();
+        builder.push(ClearBuffer {
+            buffer,
+            offset: 0,
+            size: Some(size),
+        });
+        drop(builder);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    let events = recorder.events();
+    assert!(events.contains(&RenderDeviceEvent::CreateBuffer {
+        label: Some("first".into()),
+        size: 1024,
+    }));
+    assert_eq!(created_buffers(&events, "second"), 0);
+    assert!(events.contains(&RenderDeviceEvent::ReuseResource { index: 0 }));
+}
+
+#[test]
+fn keeps_history_resources_across_frames() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    for size in [256, 256, 256, 512] {
+        let mut graph = FrameGraph::default();
+        let output = import_output(&mut graph, &device);
+        let history = graph.create_history(
+            "history",
+            TransientBufferDescriptor {
+                size,
+                ..buffer_desc("history")
+            },
+        );
+
+        {
+            let mut builder = PassBuilder::new(PassNodeBuilder::new("resolve", &mut graph));
+            let source = builder.read(history.previous);
+            let destination = builder.write(output);
+
+            builder.push(CopyBufferToBuffer {
+                source,
+                source_offset: 0,
+                destination,
+                destination_offset: 0,
+                size: Some(256),
+            });
+        }
+        clear_pass(&mut graph, "accumulate", history.current);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    let events = recorder.events();
+    assert_eq!(
+        executed_passes(&events),
+        ["resolve", "accumulate"].repeat(4)
+    );
+    assert_eq!(created_buffers(&events, "history"), 4);
+    assert!(events.contains(&RenderDeviceEvent::CreateBuffer {
+        label: Some("history".into()),
+        size: 512,
+    }));
+}
+
+/// Records which physical buffers the two halves of a history resource resolved to.
+struct CaptureHistory {
+    current: Ref<TransientBuffer, ResourceWrite>,
+    previous: Ref<TransientBuffer, ResourceRead>,
+    captured: Arc<Mutex<Vec<(GpuBuffer, GpuBuffer)>>>,
+}
+
+impl PassCommand for CaptureHistory {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let current = context.try_get_resource(&self.current)?.resource.clone();
+        let previous = context.try_get_resource(&self.previous)?.resource.clone();
+        self.captured.lock().unwrap().push((current, previous));
+
+        Ok(())
+    }
+}
+
+#[test]
+fn swaps_history_only_after_frames_that_use_it() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let captured = Arc::new(Mutex::new(vec![]));
+
+    for use_history in [true, false, true] {
+        let mut graph = FrameGraph::default();
+
+        if use_history {
+            let history = graph.create_history("history", buffer_desc("history"));
+
+            let mut builder = PassBuilder::new(PassNodeBuilder::new("capture", &mut graph));
+            let current = builder.write(history.current);
+            let previous = builder.read(history.previous);
+
+            builder.push(CaptureHistory {
+                current,
+                previous,
+                captured: captured.clone(),
+            });
+        }
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.len(), 2);
+    assert_eq!(captured[1].1, captured[0].0);
+    assert_eq!(captured[1].0, captured[0].1);
+}
+
+#[test]
+fn keeps_history_usages_in_retained_mode() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+    graph.set_retained(true);
+
+    let mut reused = vec![];
+
+    for _ in 0..3 {
+        let output = import_output(&mut graph, &device);
+        let history = graph.create_history(
+            "history",
+            TransientBufferDescriptor {
+                usage: BufferUsages::COPY_DST,
+                ..buffer_desc("history")
+            },
+        );
+
+        copy_pass(&mut graph, "resolve", history.previous, output);
+        clear_pass(&mut graph, "accumulate", history.current);
+
+        graph.compile().unwrap();
+        reused.push(graph.compiled_frame_graph().unwrap().is_reused());
+
+        let mut context =
+            FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+        graph.execute(&mut context).unwrap();
+        cache.end_frame();
+    }
+
+    assert_eq!(reused, [false, true, true]);
+    assert_eq!(
+        executed_passes(&recorder.events()),
+        ["resolve", "accumulate"].repeat(3)
+    );
+    assert_eq!(created_buffers(&recorder.events(), "history"), 2);
+}
+
+#[test]
+fn hands_exported_resources_back_to_the_caller() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    let mut graph = FrameGraph::default();
+    let baked = graph.create("baked", buffer_desc("baked"));
+    let baked = clear_pass(&mut graph, "bake", baked);
+    let exported = graph.export(&baked);
+
+    graph.compile().unwrap();
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    let baked = context.take_exported(&exported).unwrap();
+    assert!(context.take_exported(&exported).is_none());
+    drop(context);
+
+    assert_eq!(baked.desc, buffer_desc("baked"));
+    assert_eq!(cache.memory_usage(), 0);
+
+    let mut graph = FrameGraph::default();
+    let output = import_output(&mut graph, &device);
+    let baked = graph.import("baked", baked);
+    copy_pass(&mut graph, "copy", baked, output);
+
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    let events = recorder.events();
+    assert_eq!(executed_passes(&events), ["bake", "copy"]);
+    assert_eq!(created_buffers(&events, "baked"), 1);
+}
+
+#[test]
+fn records_passes_on_multiple_threads() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let first = graph.create("first", buffer_desc("scratch"));
+    let second = graph.create("second", buffer_desc("scratch"));
+
+    let first = clear_pass(&mut graph, "clear_first", first);
+    let output = copy_pass(&mut graph, "copy_first", first, output);
+    let second = clear_pass(&mut graph, "clear_second", second);
+    copy_pass(&mut graph, "copy_second", second, output);
+
+    recorder.take_events();
+    graph.compile().unwrap();
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache)
+        .with_recording_pool(Arc::new(RecordingThreadPool::new(3)));
+    graph.execute(&mut context).unwrap();
+
+    assert_eq!(context.finish().len(), 4);
+
+    let events = recorder.take_events();
+    assert_eq!(
+        executed_passes(&events),
+        ["clear_first", "copy_first", "clear_second", "copy_second"]
+    );
+    assert_eq!(created_buffers(&events, "scratch"), 1);
+    assert_eq!(cache.memory_usage(), 256);
+}
+
+/// Records the thread the pass was recorded on.
+struct CaptureThread {
+    buffer: Ref<TransientBuffer, ResourceWrite>,
+    threads: Arc<Mutex<Vec<Thread>>>,
+}
+
+impl PassCommand for CaptureThread {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        context.try_get_resource(&self.buffer)?;
+
+        self.threads.lock().unwrap().push(thread::current());
+
+        Ok(())
+    }
+}
+
+#[test]
+fn reuses_recording_threads_across_frames() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let recording_pool = Arc::new(RecordingThreadPool::new(2));
+    let threads = Arc::new(Mutex::new(vec![]));
+
+    for _ in 0..3 {
+        let mut graph = FrameGraph::default();
+
+        for name in ["first", "second", "third", "fourth"] {
+            let buffer = graph.create(name, buffer_desc(name));
+
+            let mut builder = PassBuilder::new(PassNodeBuilder::new(name, &mut graph));
+            let buffer = builder.write(buffer);
+            builder.push(CaptureThread {
+                buffer,
+                threads: threads.clone(),
+            });
+            builder.make_side_effect();
+        }
+
+        graph.compile().unwrap();
+
+        let mut context =
+            FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache)
+                .with_recording_pool(recording_pool.clone());
+        graph.execute(&mut context).unwrap();
+        cache.end_frame();
+    }
+
+    let threads = threads.lock().unwrap();
+    let thread_ids = threads.iter().map(Thread::id).collect::<HashSet<_>>();
+
+    assert_eq!(threads.len(), 12);
+    assert!(thread_ids.len() <= recording_pool.threads());
+    assert!(!thread_ids.contains(&thread::current().id()));
+    assert!(threads.iter().all(|thread| {
+        thread
+            .name()
+            .is_some_and(|name| name.starts_with("frame-graph-recording-"))
+    }));
+}
+
+/// Panics when recording, or marks itself finished after a delay.
+struct RecordOrPanic {
+    buffer: Ref<TransientBuffer, ResourceWrite>,
+    panics: bool,
+    finished: Arc<AtomicBool>,
+}
+
+impl PassCommand for RecordOrPanic {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        context.try_get_resource(&self.buffer)?;
+
+        if self.panics {
+            panic!("recording failed");
+        }
+
+        thread::sleep(Duration::from_millis(50));
+        self.finished.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn waits_for_other_recording_threads_before_resuming_panics() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let recording_pool = Arc::new(RecordingThreadPool::new(2));
+    let finished = Arc::new(AtomicBool::new(false));
+    let mut graph = FrameGraph::default();
+
+    for (name, panics) in [("panic", true), ("slow", false)] {
+        let buffer = graph.create(name, buffer_desc(name));
+
+        let mut builder = PassBuilder::new(PassNodeBuilder::new(name, &mut graph));
+        let buffer = builder.write(buffer);
+        builder.push(RecordOrPanic {
+            buffer,
+            panics,
+            finished: finished.clone(),
+        });
+        builder.make_side_effect();
+    }
+
+    graph.compile().unwrap();
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache)
+        .with_recording_pool(recording_pool.clone());
+    let result = catch_unwind(AssertUnwindSafe(|| graph.execute(&mut context)));
+
+    assert!(result.is_err());
+    assert!(finished.load(Ordering::SeqCst));
+}
+
+#[test]
+fn submits_command_buffers_in_batches() {
+    let (device, queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let scratch = graph.create("scratch", buffer_desc("scratch"));
+    let scratch = clear_pass(&mut graph, "clear_scratch", scratch);
+    let output = copy_pass(&mut graph, "copy", scratch, output);
+    clear_pass(&mut graph, "clear_output", output);
+
+    recorder.take_events();
+    graph.compile().unwrap();
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache)
+        .with_render_queue(RenderQueue::new(queue))
+        .with_submit_batch_size(2);
+    graph.execute(&mut context).unwrap();
+
+    let submitted = |events: &[RenderDeviceEvent]| {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                RenderDeviceEvent::Submit { command_buffers } => Some(*command_buffers),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(submitted(&recorder.take_events()), [2]);
+
+    context.submit();
+
+    assert_eq!(submitted(&recorder.take_events()), [1]);
+    assert!(context.finish().is_empty());
+}
+
+fn present_frame(
+    device: &RenderDevice,
+    recorder: &RenderDeviceRecorder,
+    present: bool,
+) -> Vec<String> {
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let color = graph.create("color", texture_desc("color"));
+    let color = clear_texture_pass(&mut graph, "composite", color);
+
+    if present {
+        graph.present(color);
+    }
+
+    recorder.take_events();
+    execute(&mut graph, device, &mut cache).unwrap();
+
+    executed_passes(&recorder.events())
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn present_keeps_writers_of_presented_texture() {
+    let (device, _queue, recorder) = create_render_device();
+
+    assert!(present_frame(&device, &recorder, false).is_empty());
+    assert_eq!(
+        present_frame(&device, &recorder, true),
+        ["composite", "present color"]
+    );
+}
+
+#[test]
+fn presents_imported_surface_texture() {
+    let (device, queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let surface_texture =
+        GpuSurfaceTexture::from_texture(device.create_texture(&TextureDescriptor {
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_DST,
+            format: TextureFormat::Bgra8Unorm,
+            ..texture_desc("swapchain").get_desc()
+        }));
+
+    let swapchain = graph.import_surface_texture("swapchain", &surface_texture);
+    let index = swapchain.raw.index;
+
+    let VirtualResource::Imported(texture) = &graph.get_resource_node(&index).resource else {
+        panic!("swapchain must be an imported resource");
+    };
+    assert_eq!(
+        texture.get_desc().usage(),
+        ResourceUsage::Texture(TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_DST)
+    );
+
+    let swapchain = clear_texture_pass(&mut graph, "composite", swapchain);
+    graph.present(swapchain);
+
+    recorder.take_events();
+    graph.compile().unwrap();
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache)
+        .with_render_queue(RenderQueue::new(queue));
+    graph.execute(&mut context).unwrap();
+    context.submit_and_present(surface_texture);
+
+    let events = recorder.events();
+    assert_eq!(executed_passes(&events), ["composite", "present swapchain"]);
+    assert_eq!(events.last(), Some(&RenderDeviceEvent::Present));
+}
+
+#[test]
+fn tracks_texture_mips_separately() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let output = import_output(&mut graph, &device);
+    let bloom = graph.create(
+        "bloom",
+        TransientTextureDescriptor {
+            label: Some("bloom".into()),
+            size: Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 3,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: vec![],
+        },
+    );
+
+    let mut builder = PassNodeBuilder::new("mip0", &mut graph);
+    let mip0 = builder.write(bloom.with_subresource(SubresourceRange::mips(0..1)));
+    drop(builder);
+
+    let mut builder = PassNodeBuilder::new("mip1", &mut graph);
+    builder.read(mip0.handle());
+    let mip1 = builder.write(mip0.handle().with_subresource(SubresourceRange::mips(1..2)));
+    drop(builder);
+
+    let mut builder = PassNodeBuilder::new("mip2", &mut graph);
+    builder.read(mip1.handle());
+    builder.write(mip1.handle().with_subresource(SubresourceRange::mips(2..3)));
+    drop(builder);
+
+    let mut builder = PassNodeBuilder::new("composite", &mut graph);
+    builder.read(mip0.handle());
+    builder.read(mip1.handle());
+    builder.write(output);
+    drop(builder);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(
+        executed_passes(&recorder.events()),
+        ["mip0", "mip1", "composite"]
+    );
+}
+
+fn bind_group_frame(
+    device: &RenderDevice,
+    cache: &mut TransientResourceCache,
+    layout: &BindGroupLayout,
+) {
+    let mut graph = FrameGraph::default();
+
+    let uniforms = graph.create(
+        "uniforms",
+        TransientBufferDescriptor {
+            label: Some("uniforms".into()),
+            size: 256,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    let uniforms = clear_pass(&mut graph, "clear", uniforms);
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("compute", &mut graph));
+    let uniforms = builder.read(uniforms);
+    let bind_group = TransientBindGroup {
+        label: Some("uniforms".into()),
+        layout: layout.clone(),
+        entries: vec![TransientBindGroupEntry {
+            binding: 0,
+            resource: TransientBindGroupResource::Buffer(TransientBindGroupBuffer {
+                buffer: uniforms,
+                size: None,
+                offset: 0,
+            }),
+        }],
+    };
+    builder
+        .create_compute_pass_builder("compute")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    execute(&mut graph, device, cache).unwrap();
+    cache.end_frame();
+}
+
+#[test]
+fn reuses_bind_groups_until_resources_are_evicted() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        }));
+
+    let created_bind_groups = |events: &[RenderDeviceEvent]| {
+        events
+            .iter()
+            .filter(|event| matches!(event, RenderDeviceEvent::CreateBindGroup { .. }))
+            .count()
+    };
+
+    bind_group_frame(&device, &mut cache, &layout);
+    bind_group_frame(&device, &mut cache, &layout);
+
+    assert_eq!(created_bind_groups(&recorder.take_events()), 1);
+    assert_eq!(cache.bind_group_cache().len(), 1);
+
+    cache.set_max_unused_frames(0);
+    cache.end_frame();
+
+    assert_eq!(cache.memory_usage(), 0);
+    assert!(cache.bind_group_cache().is_empty());
+
+    bind_group_frame(&device, &mut cache, &layout);
+
+    assert_eq!(created_bind_groups(&recorder.take_events()), 1);
+}
+
+#[test]
+fn drops_bind_groups_of_recreated_history_resources() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        }));
+
+    for size in [256, 256, 512] {
+        let mut graph = FrameGraph::default();
+        let history = graph.create_history(
+            "history",
+            TransientBufferDescriptor {
+                size,
+                ..buffer_desc("history")
+            },
+        );
+
+        let mut builder = PassBuilder::new(PassNodeBuilder::new("accumulate", &mut graph));
+        let current = builder.write(history.current);
+        let bind_group = TransientBindGroup {
+            label: Some("history".into()),
+            layout: layout.clone(),
+            entries: vec![TransientBindGroupEntry {
+                binding: 0,
+                resource: TransientBindGroupResource::StorageBuffer(
+                    TransientBindGroupStorageBuffer {
+                        buffer: current,
+                        size: None,
+                        offset: 0,
+                    },
+                ),
+            }],
+        };
+        builder
+            .create_compute_pass_builder("accumulate")
+            .set_bind_group(0, &bind_group, &[])
+            .create_compute_pass_builder();
+        drop(builder);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    // The two halves of the first pair each got a bind group, which went away with
+    // the pair once the descriptor changed.
+    assert_eq!(cache.bind_group_cache().len(), 1);
+}
+
+#[test]
+fn binds_storage_texture_views_written_by_passes() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let layout = |ty| {
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty,
+                count: None,
+            }],
+        }))
+    };
+    let storage_layout = layout(wgpu::BindingType::StorageTexture {
+        access: wgpu::StorageTextureAccess::WriteOnly,
+        format: TextureFormat::Rgba8Unorm,
+        view_dimension: wgpu::TextureViewDimension::D2,
+    });
+    let sampled_layout = layout(wgpu::BindingType::Texture {
+        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+        view_dimension: wgpu::TextureViewDimension::D2,
+        multisampled: false,
+    });
+
+    let color = graph.create("color", texture_desc("color"));
+    let index = color.raw.index;
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("write", &mut graph));
+    let color = builder.write(color);
+    let bind_group = TransientBindGroup {
+        label: Some("storage".into()),
+        layout: storage_layout,
+        entries: vec![TransientBindGroupEntry {
+            binding: 0,
+            resource: TransientBindGroupResource::StorageTextureView(
+                TransientBindGroupStorageTextureView {
+                    texture: color.clone(),
+                    texture_view_desc: TextureViewDescriptor::default(),
+                },
+            ),
+        }],
+    };
+    builder
+        .create_compute_pass_builder("write")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    drop(builder);
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("read", &mut graph));
+    let color = builder.read(color.handle());
+    let bind_group = TransientBindGroup {
+        label: Some("sampled".into()),
+        layout: sampled_layout,
+        entries: vec![TransientBindGroupEntry {
+            binding: 0,
+            resource: TransientBindGroupResource::TextureView(TransientBindGroupTextureView {
+                texture: color,
+                texture_view_desc: TextureViewDescriptor::default(),
+            }),
+        }],
+    };
+    builder
+        .create_compute_pass_builder("read")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    graph.compile().unwrap();
+
+    let VirtualResource::Setuped(desc) = &graph.get_resource_node(&index).resource else {
+        panic!("color must be a transient resource");
+    };
+    assert_eq!(
+        desc.usage(),
+        ResourceUsage::Texture(TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING)
+    );
+
+    recorder.take_events();
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    assert_eq!(executed_passes(&recorder.take_events()), ["write", "read"]);
+    assert_eq!(cache.bind_group_cache().len(), 2);
+}
+
+#[test]
+fn shares_texture_views_per_texture_and_descriptor() {
+    let (device, _queue, _recorder) = create_render_device();
+    let cache = TextureViewCache::default();
+
+    let [color, normal] = ["color", "normal"].map(|label| {
+        device.create_texture(&TextureDescriptor {
+            label: Some(label.into()),
+            size: Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 2,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: vec![],
+        })
+    });
+
+    let full = TextureViewDescriptor::default();
+    let mip = TextureViewDescriptor {
+        base_mip_level: 1,
+        mip_level_count: Some(1),
+        ..Default::default()
+    };
+
+    let view = cache.get_or_create(&color, &full);
+
+    assert_eq!(cache.get_or_create(&color, &full), view);
+    assert_eq!(cache.get_or_create(&color.clone(), &full), view);
+    assert_ne!(cache.get_or_create(&color, &mip), view);
+    assert_ne!(cache.get_or_create(&normal, &full), view);
+    assert_eq!(cache.len(), 3);
+}
+
+/// Creates a view of the texture through the pass context.
+struct CreateView {
+    texture: Ref<TransientTexture, ResourceWrite>,
+}
+
+impl PassCommand for CreateView {
+    fn execute(&self, context: &mut PassContext) -> Result<(), FrameGraphError> {
+        let texture = context.try_get_resource(&self.texture)?;
+        context
+            .texture_view_cache()
+            .get_or_create(&texture.resource, &TextureViewDescriptor::default());
+
+        Ok(())
+    }
+}
+
+#[test]
+fn drops_texture_views_when_textures_are_evicted() {
+    let (device, _queue, _recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    for _ in 0..2 {
+        let mut graph = FrameGraph::default();
+        let color = graph.create(
+            "color",
+            TransientTextureDescriptor {
+                usage: TextureUsages::TEXTURE_BINDING,
+                ..texture_desc("color")
+            },
+        );
+
+        let mut builder = PassBuilder::new(PassNodeBuilder::new("view", &mut graph));
+        let texture = builder.write(color);
+        builder.push(CreateView { texture });
+        builder.make_side_effect();
+        drop(builder);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    assert_eq!(cache.texture_view_cache().len(), 1);
+
+    cache.set_max_unused_frames(0);
+    cache.end_frame();
+
+    assert_eq!(cache.memory_usage(), 0);
+    assert!(cache.texture_view_cache().is_empty());
+}
+
+#[test]
+fn tracks_storage_buffer_bindings_as_writes() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        }));
+
+    let output = import_output(&mut graph, &device);
+    let particles = graph.create(
+        "particles",
+        TransientBufferDescriptor {
+            usage: BufferUsages::empty(),
+            ..buffer_desc("particles")
+        },
+    );
+    let index = particles.raw.index;
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("simulate", &mut graph));
+    let particles = builder.write(particles);
+    let bind_group = TransientBindGroup {
+        label: Some("particles".into()),
+        layout,
+        entries: vec![TransientBindGroupEntry {
+            binding: 0,
+            resource: TransientBindGroupResource::StorageBuffer(TransientBindGroupStorageBuffer {
+                buffer: particles.clone(),
+                size: None,
+                offset: 0,
+            }),
+        }],
+    };
+    builder
+        .create_compute_pass_builder("simulate")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    drop(builder);
+
+    copy_pass(&mut graph, "copy", particles.handle(), output);
+
+    recorder.take_events();
+    graph.compile().unwrap();
+
+    let VirtualResource::Setuped(desc) = &graph.get_resource_node(&index).resource else {
+        panic!("particles must be a transient resource");
+    };
+    assert_eq!(
+        desc.usage(),
+        ResourceUsage::Buffer(BufferUsages::STORAGE | BufferUsages::COPY_SRC)
+    );
+
+    let mut context = FrameGraphContext::new(PipelineContainer::new(vec![]), &device, &mut cache);
+    graph.execute(&mut context).unwrap();
+
+    assert_eq!(executed_passes(&recorder.events()), ["simulate", "copy"]);
+}
+
+fn sampler(device: &RenderDevice, label: &str) -> Sampler {
+    Sampler::new(device.create_sampler(&SamplerDescriptor {
+        label: Some(label.into()),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Nearest,
+        lod_min_clamp: 0.0,
+        lod_max_clamp: 32.0,
+        compare: None,
+        anisotropy_clamp: 1,
+        border_color: None,
+    }))
+}
+
+#[test]
+fn binds_sampler_arrays() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: NonZero::new(2),
+            }],
+        }));
+    let samplers = vec![sampler(&device, "linear"), sampler(&device, "shadow")];
+
+    recorder.take_events();
+
+    for _ in 0..2 {
+        let mut graph = FrameGraph::default();
+
+        let mut builder = PassBuilder::new(PassNodeBuilder::new("filter", &mut graph));
+        let bind_group = TransientBindGroup {
+            label: Some("samplers".into()),
+            layout: layout.clone(),
+            entries: vec![TransientBindGroupEntry {
+                binding: 0,
+                resource: TransientBindGroupResource::SamplerArray(samplers.clone()),
+            }],
+        };
+        builder
+            .create_compute_pass_builder("filter")
+            .set_bind_group(0, &bind_group, &[])
+            .create_compute_pass_builder();
+        builder.make_side_effect();
+        drop(builder);
+
+        execute(&mut graph, &device, &mut cache).unwrap();
+        cache.end_frame();
+    }
+
+    let events = recorder.take_events();
+    assert_eq!(executed_passes(&events), ["filter", "filter"]);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| matches!(event, RenderDeviceEvent::CreateBindGroup { .. }))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn binds_buffer_arrays() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: NonZero::new(2),
+            }],
+        }));
+
+    let lights: Vec<_> = ["lights0", "lights1", "unused"]
+        .into_iter()
+        .map(|name| {
+            let lights = graph.create(
+                name,
+                TransientBufferDescriptor {
+                    usage: BufferUsages::empty(),
+                    ..buffer_desc(name)
+                },
+            );
+            clear_pass(&mut graph, name, lights)
+        })
+        .collect();
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("shade", &mut graph));
+    let buffers = lights[..2]
+        .iter()
+        .map(|lights| TransientBindGroupBuffer {
+            buffer: builder.read(lights.clone()),
+            size: None,
+            offset: 0,
+        })
+        .collect();
+    let bind_group = TransientBindGroup {
+        label: Some("lights".into()),
+        layout,
+        entries: vec![TransientBindGroupEntry {
+            binding: 0,
+            resource: TransientBindGroupResource::BufferArray(buffers),
+        }],
+    };
+    builder
+        .create_compute_pass_builder("shade")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    let events = recorder.take_events();
+    assert_eq!(executed_passes(&events), ["lights0", "lights1", "shade"]);
+    assert_eq!(created_buffers(&events, "unused"), 0);
+    assert_eq!(cache.bind_group_cache().len(), 1);
+}
+
+#[test]
+fn builds_bindless_buffer_tables() {
+    let (device, _queue, recorder) = create_render_device();
+    let mut cache = TransientResourceCache::default();
+    let mut graph = FrameGraph::default();
+
+    let layout =
+        BindGroupLayout::new(device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: vec![wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: NonZero::new(4),
+            }],
+        }));
+
+    let meshes: Vec<_> = ["mesh0", "mesh1", "mesh2"]
+        .into_iter()
+        .map(|name| {
+            let mesh = graph.create(
+                name,
+                TransientBufferDescriptor {
+                    usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                    ..buffer_desc(name)
+                },
+            );
+            clear_pass(&mut graph, name, mesh)
+        })
+        .collect();
+
+    let mut builder = PassBuilder::new(PassNodeBuilder::new("cull", &mut graph));
+    let meshes: Vec<_> = meshes
+        .into_iter()
+        .map(|mesh| TransientBindGroupBuffer {
+            buffer: builder.read(mesh),
+            size: None,
+            offset: 0,
+        })
+        .collect();
+
+    let mut table = BindlessBufferTable::new(2);
+    assert_eq!(table.insert(meshes[0].clone()), Some(0));
+    assert_eq!(table.insert(meshes[1].clone()), Some(1));
+    assert_eq!(table.insert(meshes[0].clone()), Some(0));
+    assert_eq!(table.insert(meshes[2].clone()), None);
+    assert_eq!(table.len(), 2);
+
+    let entry = table.entry(0);
+    assert!(matches!(
+        &entry.resource,
+        TransientBindGroupResource::BufferArray(buffers) if buffers.len() == 2
+    ));
+
+    let bind_group = TransientBindGroup {
+        label: Some("meshes".into()),
+        layout,
+        entries: vec![entry],
+    };
+    builder
+        .create_compute_pass_builder("cull")
+        .set_bind_group(0, &bind_group, &[])
+        .create_compute_pass_builder();
+    builder.make_side_effect();
+    drop(builder);
+
+    recorder.take_events();
+    execute(&mut graph, &device, &mut cache).unwrap();
+
+    assert_eq!(
+        executed_passes(&recorder.events()),
+        ["mesh0", "mesh1", "mesh2", "cull"]
+    );
+    assert_eq!(cache.bind_group_cache().len(), 1);
+}